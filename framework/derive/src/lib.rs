@@ -0,0 +1,208 @@
+//! `#[derive(Parse)]`, which builds a parser out of the parser combinators of
+//! `framework::parsers`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Fields, LitByteStr, LitStr,
+};
+
+/// Implements `framework::parsers::Parse`.
+///
+/// The fields of a struct are parsed in order, by the `Parse` implementation
+/// of their type, and text around them is given with `#[parse(...)]`:
+///
+/// - on the struct, `prefix`, `suffix` and `sep`, the text between fields,
+/// - on a field, `prefix`, `suffix`, and `with`, an expression of the parser
+///   to use instead,
+/// - on either, `context`, which labels errors like the `context` combinator.
+///
+/// Text is matched with `phrase`, so whitespace in it is flexible. An enum of
+/// unit variants parses one of its variants, each matching its `token`,
+/// which defaults to the name of the variant. Longer tokens are tried first,
+/// such that a token that is a prefix of another doesn't shadow it.
+///
+/// The generated code refers to the framework as `::framework`, the name the
+/// crates of this workspace depend on it by. Crates that depend on it by
+/// another name set the path with `#[parse(crate = "...")]` on the type.
+#[proc_macro_derive(Parse, attributes(parse))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match derive(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    prefix: Option<LitStr>,
+    suffix: Option<LitStr>,
+    sep: Option<LitStr>,
+    context: Option<LitStr>,
+    with: Option<TokenStream2>,
+    token: Option<LitStr>,
+    krate: Option<syn::Path>,
+}
+
+fn options(attrs: &[Attribute], allowed: &[&str]) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("parse")) {
+        attr.parse_nested_meta(|meta| {
+            let key = meta.path.get_ident().map(|i| i.to_string());
+            let key = key.as_deref().unwrap_or_default();
+            if !allowed.contains(&key) {
+                let expected = allowed.join(", ");
+                return Err(meta.error(format!("expected one of: {expected}")));
+            }
+            let value: LitStr = meta.value()?.parse()?;
+            match key {
+                "prefix" => options.prefix = Some(value),
+                "suffix" => options.suffix = Some(value),
+                "sep" => options.sep = Some(value),
+                "context" => options.context = Some(value),
+                "with" => options.with = Some(value.parse()?),
+                "crate" => options.krate = Some(value.parse()?),
+                _ => options.token = Some(value),
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+fn phrase(krate: &TokenStream2, text: &LitStr) -> TokenStream2 {
+    let text = LitByteStr::new(text.value().as_bytes(), text.span());
+    quote!(#krate::parsers::phrase(#text))
+}
+
+/// Surrounds a parser with the prefix, suffix and context of its options.
+fn surround(krate: &TokenStream2, parser: TokenStream2, options: &Options) -> TokenStream2 {
+    let mut parser = parser;
+    if let Some(prefix) = &options.prefix {
+        let prefix = phrase(krate, prefix);
+        parser = quote!(#prefix.then(#parser));
+    }
+    if let Some(suffix) = &options.suffix {
+        let suffix = phrase(krate, suffix);
+        parser = quote!(#parser.trailed(#suffix));
+    }
+    if let Some(context) = &options.context {
+        parser = quote!(#parser.context(#context));
+    }
+    parser
+}
+
+fn derive(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let allowed: &[&str] = match &input.data {
+        Data::Struct(_) => &["prefix", "suffix", "sep", "context", "crate"],
+        _ => &["prefix", "suffix", "context", "crate"],
+    };
+    let options = options(&input.attrs, allowed)?;
+    let krate = match &options.krate {
+        Some(path) => quote!(#path),
+        None => quote!(::framework),
+    };
+    let krate = &krate;
+    let parser = match &input.data {
+        Data::Struct(data) => {
+            let parser = struct_parser(krate, &data.fields, &options)?;
+            surround(krate, parser, &options)
+        }
+        Data::Enum(data) => {
+            let mut variants = Vec::new();
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    let message = "Parse can only be derived for enums of unit variants";
+                    return Err(syn::Error::new(variant.span(), message));
+                }
+                let name = &variant.ident;
+                let token = options_token(&variant.attrs)?
+                    .unwrap_or_else(|| LitStr::new(&name.to_string(), name.span()));
+                let len = token.value().len();
+                let token = phrase(krate, &token);
+                variants.push((len, quote!(#token.map(|()| Self::#name))));
+            }
+            variants.sort_by_key(|&(len, _)| std::cmp::Reverse(len));
+            let variants: Vec<_> = variants.into_iter().map(|(_, parser)| parser).collect();
+            let Some((first, rest)) = variants.split_first() else {
+                let message = "Parse can't be derived for enums without variants";
+                return Err(syn::Error::new(input.ident.span(), message));
+            };
+            surround(krate, quote!(#first #(.or(#rest))*), &options)
+        }
+        Data::Union(_) => {
+            let message = "Parse can't be derived for unions";
+            return Err(syn::Error::new(input.ident.span(), message));
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::parsers::Parse for #name #ty_generics #where_clause {
+            fn parser<'s>() -> impl #krate::parsers::Parser<'s, Output = Self>
+            where
+                Self: 's,
+            {
+                #[allow(unused_imports)]
+                use #krate::parsers::{ParserCombiExt as _, ParserMultiExt as _};
+                #parser
+            }
+        }
+    })
+}
+
+fn options_token(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    Ok(options(attrs, &["token"])?.token)
+}
+
+/// Parses the fields in order, and builds the struct from their outputs.
+fn struct_parser(
+    krate: &TokenStream2,
+    fields: &Fields,
+    struct_options: &Options,
+) -> syn::Result<TokenStream2> {
+    let mut parser = None;
+    let mut pattern = quote!(());
+    let mut members = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let field_options = options(&field.attrs, &["prefix", "suffix", "context", "with"])?;
+        let ty = &field.ty;
+        let field_parser = match &field_options.with {
+            Some(with) => quote!((#with)),
+            None => quote!(<#ty as #krate::parsers::Parse>::parser()),
+        };
+        let field_parser = surround(krate, field_parser, &field_options);
+
+        let binding = format_ident!("field_{index}");
+        (parser, pattern) = match parser {
+            None => (Some(field_parser), quote!(#binding)),
+            Some(parser) => {
+                let field_parser = match &struct_options.sep {
+                    Some(sep) => {
+                        let sep = phrase(krate, sep);
+                        quote!(#sep.then(#field_parser))
+                    }
+                    None => field_parser,
+                };
+                (
+                    Some(quote!(#parser.and(#field_parser))),
+                    quote!((#pattern, #binding)),
+                )
+            }
+        };
+        members.push(match &field.ident {
+            Some(ident) => quote!(#ident: #binding),
+            None => {
+                let index = syn::Index::from(index);
+                quote!(#index: #binding)
+            }
+        });
+    }
+
+    // A struct without fields parses nothing but its prefix and suffix.
+    let parser = parser.unwrap_or_else(|| quote!(#krate::parsers::phrase(b"")));
+    Ok(quote!(#parser.map(|#pattern| Self { #(#members),* })))
+}
@@ -0,0 +1,122 @@
+use crate::result::Result;
+use std::path::{Path, PathBuf};
+
+/// The known-correct answers of a single day, stored as one `part: answer`
+/// line per part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answers {
+    path: PathBuf,
+    entries: Vec<(String, String)>,
+    is_modified: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    /// There is no recorded answer for this part.
+    Unknown,
+    /// The answer matches the recorded answer.
+    Correct,
+    /// The answer differs from the recorded answer, or the part failed.
+    Incorrect,
+    /// There was no recorded answer, and the answer has now been recorded.
+    Recorded,
+}
+
+impl Answers {
+    pub fn path(directory: &Path, day: u32) -> PathBuf {
+        directory.join(format!("{day:0>2}.txt"))
+    }
+
+    /// Loads the answers of a day, a missing file means no answers are known.
+    pub fn load(directory: &Path, day: u32) -> Result<Answers> {
+        let path = Self::path(directory, day);
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents
+                .lines()
+                .filter_map(|line| line.split_once(": "))
+                .map(|(part, answer)| (part.to_owned(), answer.to_owned()))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Answers {
+            path,
+            entries,
+            is_modified: false,
+        })
+    }
+
+    pub fn get(&self, part: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(p, _)| p == part)
+            .map(|(_, answer)| answer.as_str())
+    }
+
+    /// Records the answer of a part, replacing any previously recorded answer.
+    pub fn insert(&mut self, part: &str, answer: &str) {
+        match self.entries.iter_mut().find(|(p, _)| p == part) {
+            Some((_, previous)) => *previous = answer.to_owned(),
+            None => self.entries.push((part.to_owned(), answer.to_owned())),
+        }
+        self.is_modified = true;
+    }
+
+    /// Compares an answer against the recorded one. When `record` is set,
+    /// answers for parts without a recorded answer are added.
+    pub fn verify(&mut self, part: &str, answer: Option<&str>, record: bool) -> Verification {
+        match (self.get(part), answer) {
+            (Some(expected), Some(answer)) if expected == answer => Verification::Correct,
+            (Some(_), _) => Verification::Incorrect,
+            (None, Some(answer)) if record => {
+                self.insert(part, answer);
+                Verification::Recorded
+            }
+            (None, _) => Verification::Unknown,
+        }
+    }
+
+    /// Writes the answers back to disk, if any were added.
+    pub fn save(&mut self) -> Result<()> {
+        if !self.is_modified {
+            return Ok(());
+        }
+        let mut contents = String::new();
+        for (part, answer) in &self.entries {
+            contents.push_str(part);
+            contents.push_str(": ");
+            contents.push_str(answer);
+            contents.push('\n');
+        }
+        if let Some(directory) = self.path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(&self.path, contents)?;
+        self.is_modified = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[rustfmt::skip]
+    fn record_and_verify() {
+        let directory = std::env::temp_dir().join(format!("aoc_answers_{}", std::process::id()));
+        let mut answers = Answers::load(&directory, 3).unwrap();
+        assert_eq!(Verification::Unknown,   answers.verify("pt1", Some("12"   ), false));
+        assert_eq!(Verification::Recorded,  answers.verify("pt1", Some("12"   ), true ));
+        assert_eq!(Verification::Recorded,  answers.verify("pt2", Some("a * b"), true ));
+        answers.save().unwrap();
+
+        let mut answers = Answers::load(&directory, 3).unwrap();
+        assert_eq!(Some("a * b"), answers.get("pt2"));
+        assert_eq!(Verification::Correct,   answers.verify("pt1", Some("12"   ), true ));
+        assert_eq!(Verification::Incorrect, answers.verify("pt1", Some("13"   ), true ));
+        assert_eq!(Verification::Incorrect, answers.verify("pt2", None,          false));
+        assert_eq!(Some("12"), answers.get("pt1"));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
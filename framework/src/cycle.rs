@@ -0,0 +1,150 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// Where the states of an iteration start repeating: the state after `start`
+/// steps is the first one to recur, and it does so every `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The lowest number of steps that ends up in the same state as `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Finds the cycle of repeatedly applying `step` to `initial`, by remembering
+/// every state until one repeats.
+pub fn find_hashed<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    for steps in 0.. {
+        match seen.entry(state) {
+            Entry::Occupied(slot) => {
+                let start = *slot.get();
+                return Cycle {
+                    start,
+                    length: steps - start,
+                };
+            }
+            Entry::Vacant(slot) => {
+                state = step(slot.key());
+                slot.insert(steps);
+            }
+        }
+    }
+    unreachable!()
+}
+
+/// Finds the cycle like `find_hashed`, but with Brent's algorithm, which
+/// keeps only a few states around, at the cost of taking more steps.
+pub fn find_brent<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    // Moves the tortoise to the hare at every power of two, until the hare
+    // catches up with it, which takes the length of the cycle.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // With the hare a cycle ahead, they meet at the start of the cycle.
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Returns the state after applying `step` to `initial` `n` times, skipping
+/// over the repetitions once the states start to repeat, such that `n` can be
+/// a billion.
+pub fn nth_state<S, F>(initial: S, mut step: F, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = initial;
+    for steps in 0..n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: steps - start,
+            };
+            return history.swap_remove(cycle.reduce(n));
+        }
+        let next = step(&state);
+        seen.insert(state.clone(), steps);
+        history.push(state);
+        state = next;
+    }
+    state
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cycles() {
+        // 3, 10, 101, 202, 805, 26, 677, 330, 901, 802, 205, 26, ...
+        let step = |&x: &u32| (x * x + 1) % 1000;
+        let expected = Cycle {
+            start: 5,
+            length: 6,
+        };
+        assert_eq!(expected, find_hashed(3, step));
+        assert_eq!(expected, find_brent(3, step));
+        assert_eq!(5, expected.reduce(1_000_000_001));
+        assert_eq!(4, expected.reduce(4));
+
+        let brute_force = |n| (0..n).fold(3, |x, _| step(&x));
+        for n in 0..20 {
+            assert_eq!(brute_force(n), nth_state(3, step, n));
+        }
+        assert_eq!(26, nth_state(3, step, 1_000_000_001));
+
+        // A cycle from the initial state, which is where a permutation ends up.
+        let rotate = |order: &[u8; 3]| [order[1], order[2], order[0]];
+        let expected = Cycle {
+            start: 0,
+            length: 3,
+        };
+        assert_eq!(expected, find_hashed(*b"abc", rotate));
+        assert_eq!(expected, find_brent(*b"abc", rotate));
+        assert_eq!(*b"bca", nth_state(*b"abc", rotate, 1_000_000_000));
+    }
+}
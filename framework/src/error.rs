@@ -1,17 +1,172 @@
+use std::{borrow::Cow, fmt, sync::Arc};
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, PartialEq, Error)]
 pub enum Error {
     #[error("io error: {0}")]
-    IoError(#[from] std::io::Error),
+    IoError(Shared<std::io::Error>),
     #[error("network error: {0}")]
-    NetworkError(#[from] Box<ureq::Error>),
+    NetworkError(Shared<ureq::Error>),
     #[error("parse error: {0}")]
-    ParseError(#[from] crate::parsers::ParseError),
+    ParseError(crate::parsers::ParseError),
     #[error("not yet implemented")]
     NotImplemented,
     #[error("invalid input '{0}'")]
-    InvalidInput(&'static str),
+    InvalidInput(Cow<'static, str>),
+    #[error("invalid argument '{0}'")]
+    InvalidArgument(String),
     #[error("no solution")]
     NoSolution,
+    #[error("{0} answer(s) did not match the recorded answers")]
+    AnswerMismatch(usize),
+    #[error("the input of day {0} is not cached, and downloading is disabled")]
+    InputUnavailable(u32),
+    #[error("no session key found, looked in: {0}")]
+    SessionKeyNotFound(String),
+    #[error("the session key in {0} is not a hexadecimal string")]
+    InvalidSessionKey(String),
+    /// What was being done when the source error occurred.
+    #[error("{context}")]
+    Context {
+        context: Cow<'static, str>,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    pub fn invalid_input(detail: impl Into<Cow<'static, str>>) -> Error {
+        Error::InvalidInput(detail.into())
+    }
+
+    /// Name of the variant, used to identify the kind of error in
+    /// machine-readable output. Context is looked through, so the name is
+    /// that of the underlying error.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Error::IoError(_) => "IoError",
+            Error::NetworkError(_) => "NetworkError",
+            Error::ParseError(_) => "ParseError",
+            Error::NotImplemented => "NotImplemented",
+            Error::InvalidInput(_) => "InvalidInput",
+            Error::InvalidArgument(_) => "InvalidArgument",
+            Error::NoSolution => "NoSolution",
+            Error::AnswerMismatch(_) => "AnswerMismatch",
+            Error::InputUnavailable(_) => "InputUnavailable",
+            Error::SessionKeyNotFound(_) => "SessionKeyNotFound",
+            Error::InvalidSessionKey(_) => "InvalidSessionKey",
+            Error::Context { source, .. } => source.variant_name(),
+        }
+    }
+
+    /// The error followed by its sources, one per line.
+    pub fn chain(&self) -> String {
+        let mut chain = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(error) = source {
+            chain += &format!("\n  caused by: {error}");
+            source = error.source();
+        }
+        chain
+    }
+}
+
+// The wrapped errors are part of the description, so they are not sources.
+impl From<crate::parsers::ParseError> for Error {
+    fn from(error: crate::parsers::ParseError) -> Error {
+        Error::ParseError(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        Error::IoError(Shared(Arc::new(error)))
+    }
+}
+
+impl From<ureq::Error> for Error {
+    fn from(error: ureq::Error) -> Error {
+        Error::NetworkError(Shared(Arc::new(error)))
+    }
+}
+
+/// Adds context to the error of a result.
+pub trait Context<T> {
+    fn context(self, context: impl Into<Cow<'static, str>>) -> Result<T, Error>;
+
+    /// Like `context`, but only formats the context when there is an error.
+    fn with_context<C, F>(self, context: F) -> Result<T, Error>
+    where
+        C: Into<Cow<'static, str>>,
+        F: FnOnce() -> C;
+}
+
+impl<T, E: Into<Error>> Context<T> for Result<T, E> {
+    fn context(self, context: impl Into<Cow<'static, str>>) -> Result<T, Error> {
+        self.with_context(|| context)
+    }
+
+    fn with_context<C, F>(self, context: F) -> Result<T, Error>
+    where
+        C: Into<Cow<'static, str>>,
+        F: FnOnce() -> C,
+    {
+        self.map_err(|error| Error::Context {
+            context: context().into(),
+            source: Box::new(error.into()),
+        })
+    }
+}
+
+/// An error that is not `Clone` itself, shared so that it can be cloned.
+/// Shared errors are equal when they are the same error, or display the same.
+#[derive(Debug)]
+pub struct Shared<E>(pub Arc<E>);
+
+impl<E> Clone for Shared<E> {
+    fn clone(&self) -> Self {
+        Shared(self.0.clone())
+    }
+}
+
+impl<E: fmt::Display> PartialEq for Shared<E> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Shared<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<E> std::ops::Deref for Shared<E> {
+    type Target = E;
+    fn deref(&self) -> &E {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parsers::ParseError;
+
+    #[test]
+    fn contexts() {
+        let result: Result<(), _> = Err(ParseError::custom(format!("bad register {}", 'q')));
+        let error = result.context("day 18").context("running").unwrap_err();
+        assert_eq!(
+            "running\n  caused by: day 18\n  caused by: parse error: bad register q",
+            error.chain()
+        );
+        assert_eq!("ParseError", error.variant_name());
+        assert_eq!(error.clone(), error);
+
+        let io = Error::from(std::io::Error::other("disk full"));
+        assert_eq!(io.clone(), io);
+        assert_eq!(Error::from(std::io::Error::other("disk full")), io);
+        assert_ne!(Error::invalid_input("disk full"), io);
+    }
 }
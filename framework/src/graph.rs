@@ -1,8 +1,13 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    cmp::{Ordering, Reverse},
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
     hash::Hash,
 };
 
+use num::Zero;
+
+/// Visits each node reachable from `initial` once, depth-first, where `visit`
+/// returns the neighbors of a node.
 pub fn flood_fill<N, I, FV>(initial: N, mut visit: FV)
 where
     N: Eq + Hash,
@@ -22,22 +27,422 @@ where
     });
 }
 
+/// Visits nodes depth-first, starting at `initial`, where `visit` returns the
+/// nodes to visit next. Nodes are not deduplicated, see `flood_fill` for that.
+/// Uses a stack of the pending iterators rather than recursion, so deep
+/// graphs don't overflow the call stack.
 pub fn dfs<N, I, FV>(initial: N, mut visit: FV)
 where
-    N: Eq + Hash,
     I: IntoIterator<Item = N>,
     FV: FnMut(N) -> I,
 {
-    pub fn dfs_impl<N, I, FV>(current: N, visit: &mut FV)
-    where
-        N: Eq + Hash,
-        I: IntoIterator<Item = N>,
-        FV: FnMut(N) -> I,
-    {
-        for node in visit(current) {
-            dfs_impl(node, visit);
+    let mut stack = vec![visit(initial).into_iter()];
+    while let Some(pending) = stack.last_mut() {
+        match pending.next() {
+            Some(node) => {
+                let next = visit(node).into_iter();
+                stack.push(next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// Visits nodes breadth-first, starting at `initial`, along with their depth,
+/// the number of steps from `initial` to them. Like `flood_fill`, each node is
+/// visited once, at its lowest depth.
+pub fn bfs<N, I, FV>(initial: N, mut visit: FV)
+where
+    N: Eq + Hash,
+    I: IntoIterator<Item = N>,
+    FV: FnMut(&N, usize) -> I,
+{
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([(initial, 0)]);
+    while let Some((node, depth)) = queue.pop_front() {
+        if visited.contains(&node) {
+            continue;
+        }
+        let next = visit(&node, depth);
+        queue.extend(next.into_iter().map(|n| (n, depth + 1)));
+        visited.insert(node);
+    }
+}
+
+/// The distance of each node reachable from `initial`, in steps, where
+/// `neighbors` returns the nodes one step away from a node.
+pub fn bfs_distances<N, I, FN>(initial: N, mut neighbors: FN) -> HashMap<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+    FN: FnMut(&N) -> I,
+{
+    let mut distances = HashMap::new();
+    bfs(initial, |node, depth| {
+        distances.insert(node.clone(), depth);
+        neighbors(node)
+    });
+    distances
+}
+
+/// A cheapest path, as found by `dijkstra` and `astar`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    /// The nodes along the path, from the initial node to the goal.
+    pub nodes: Vec<N>,
+}
+
+/// Finds the cheapest path from `initial` to a node for which `is_goal`
+/// holds, where `neighbors` returns the nodes one step away from a node,
+/// along with the cost of that step.
+pub fn dijkstra<N, C, I, FN, FG>(initial: N, neighbors: FN, is_goal: FG) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (N, C)>,
+    FN: FnMut(&N) -> I,
+    FG: FnMut(&N) -> bool,
+{
+    astar(initial, neighbors, |_| C::zero(), is_goal)
+}
+
+/// Like `dijkstra`, but explores the nodes that `heuristic` estimates to be
+/// closest to a goal first. The path is the cheapest as long as the estimate
+/// never exceeds the actual cost, such as the Manhattan distance on a grid
+/// where every step costs 1.
+pub fn astar<N, C, I, FN, FH, FG>(
+    initial: N,
+    mut neighbors: FN,
+    mut heuristic: FH,
+    mut is_goal: FG,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (N, C)>,
+    FN: FnMut(&N) -> I,
+    FH: FnMut(&N) -> C,
+    FG: FnMut(&N) -> bool,
+{
+    struct Node<N, C> {
+        node: N,
+        parent: Option<usize>,
+        cost: C,
+    }
+
+    let mut queue = BinaryHeap::from([(Reverse(heuristic(&initial)), C::zero(), 0)]);
+    let mut indices = HashMap::from([(initial.clone(), 0)]);
+    let mut nodes = vec![Node {
+        node: initial,
+        parent: None,
+        cost: C::zero(),
+    }];
+    while let Some((_, cost, index)) = queue.pop() {
+        if cost > nodes[index].cost {
+            // A cheaper path to the node was found since.
+            continue;
+        }
+        let node = nodes[index].node.clone();
+        if is_goal(&node) {
+            let mut path = Vec::new();
+            let mut current = Some(index);
+            while let Some(i) = current {
+                path.push(nodes[i].node.clone());
+                current = nodes[i].parent;
+            }
+            path.reverse();
+            return Some(Path { cost, nodes: path });
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            let next_index = match indices.entry(next) {
+                Entry::Occupied(slot) if next_cost < nodes[*slot.get()].cost => *slot.get(),
+                Entry::Occupied(_) => continue,
+                Entry::Vacant(slot) => {
+                    nodes.push(Node {
+                        node: slot.key().clone(),
+                        parent: None,
+                        cost: next_cost,
+                    });
+                    *slot.insert(nodes.len() - 1)
+                }
+            };
+            let next = &mut nodes[next_index];
+            next.parent = Some(index);
+            next.cost = next_cost;
+            let estimate = next_cost + heuristic(&next.node);
+            queue.push((Reverse(estimate), next_cost, next_index));
+        }
+    }
+    None
+}
+
+/// Disjoint sets of the elements `0..len`, which start out on their own, and
+/// are merged with `union`.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the representative of the set containing `element`, which is
+    /// the same for all elements of a set.
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // Point everything along the way at the root, to shorten later finds.
+        let mut current = element;
+        while current != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returns whether they were
+    /// disjoint before.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        // Hang the shallower tree under the deeper one.
+        let (root, child) = match self.ranks[a].cmp(&self.ranks[b]) {
+            Ordering::Less => (b, a),
+            Ordering::Greater => (a, b),
+            Ordering::Equal => {
+                self.ranks[a] += 1;
+                (a, b)
+            }
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        self.count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of elements in the set containing `element`.
+    pub fn size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+}
+
+/// The connected components of a graph, see `connected_components`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components<N: Eq + Hash> {
+    /// The component of each node, numbered from 0 in order of the first node
+    /// of each component.
+    pub ids: HashMap<N, usize>,
+    /// The number of nodes in each component.
+    pub sizes: Vec<usize>,
+}
+
+impl<N: Eq + Hash> Components<N> {
+    /// The number of components.
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+}
+
+/// Groups `nodes` into the components connected by the edges from each node
+/// to its `neighbors`. Neighbors that are not among `nodes` are ignored, such
+/// that the neighbors of a `Vec2` can be its orthogonal neighbors, regardless
+/// of whether they are part of the graph.
+pub fn connected_components<N, I, FN>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbors: FN,
+) -> Components<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+    FN: FnMut(&N) -> I,
+{
+    let mut ids = HashMap::new();
+    let mut order = Vec::new();
+    for node in nodes {
+        if let Entry::Vacant(slot) = ids.entry(node) {
+            order.push(slot.key().clone());
+            slot.insert(order.len() - 1);
         }
     }
 
-    dfs_impl(initial, &mut visit)
+    let mut sets = UnionFind::new(order.len());
+    for (index, node) in order.iter().enumerate() {
+        for neighbor in neighbors(node) {
+            if let Some(&neighbor) = ids.get(&neighbor) {
+                sets.union(index, neighbor);
+            }
+        }
+    }
+
+    let mut component_of_root = vec![None; order.len()];
+    let mut sizes = Vec::new();
+    let components: Vec<usize> = (0..order.len())
+        .map(|index| {
+            let root = sets.find(index);
+            *component_of_root[root].get_or_insert_with(|| {
+                sizes.push(sets.sizes[root]);
+                sizes.len() - 1
+            })
+        })
+        .collect();
+    for id in ids.values_mut() {
+        *id = components[*id];
+    }
+    Components { ids, sizes }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn traversals() {
+        // A path deep enough to overflow the stack when recursing.
+        let mut count = 0;
+        dfs(0, |n: u32| {
+            count += 1;
+            (n < 1_000_000).then_some(n + 1)
+        });
+        assert_eq!(1_000_001, count);
+
+        let mut order = Vec::new();
+        dfs(1, |n| {
+            order.push(n);
+            [n * 2, n * 2 + 1].into_iter().filter(|&n| n < 8)
+        });
+        assert_eq!(vec![1, 2, 4, 5, 3, 6, 7], order);
+
+        // A cycle, 0 -> 1 -> 2 -> 3 -> 0, with a shortcut from 0 to 2.
+        let edges = |n: &u32| [(n + 1) % 4].into_iter().chain((*n == 0).then_some(2));
+        let mut depths = Vec::new();
+        bfs(0, |&n, depth| {
+            depths.push((n, depth));
+            edges(&n)
+        });
+        assert_eq!(vec![(0, 0), (1, 1), (2, 1), (3, 2)], depths);
+
+        let mut visited = 0;
+        flood_fill(0, |n| {
+            visited += 1;
+            edges(n)
+        });
+        assert_eq!(4, visited);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn shortest_paths() {
+        use crate::{grid::Grid, offsets::{Neighbors, Offset}, parsers::*, vecs::Vec2};
+
+        let maze: Grid<u8> = grid(pattern!(b'.' | b'#')).execute(b"\
+...#
+.#.#
+.#..
+...#").unwrap();
+        let open = |pos: &Vec2<usize>| {
+            maze.neighbors(*pos, &Offset::ORTHOGONAL)
+                .filter(|&(_, &c)| c == b'.')
+                .map(|(pos, _)| pos)
+                .collect::<Vec<_>>()
+        };
+        let start = Vec2::new(0, 0);
+        let goal = Vec2::new(3, 2);
+
+        let distances = bfs_distances(start, open);
+        assert_eq!(Some(&5), distances.get(&goal));
+        assert_eq!(None, distances.get(&Vec2::new(3, 3)));
+        assert_eq!(11, distances.len());
+
+        let expected = [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (3, 2)].map(Vec2::from).to_vec();
+        let steps = |pos: &Vec2<usize>| open(pos).into_iter().map(|pos| (pos, 1));
+        let path = dijkstra(start, steps, |&pos| pos == goal).unwrap();
+        assert_eq!(Path { cost: 5, nodes: expected.clone() }, path);
+        let manhattan = |pos: &Vec2<usize>| pos.x.abs_diff(goal.x) + pos.y.abs_diff(goal.y);
+        let path = astar(start, steps, manhattan, |&pos| pos == goal).unwrap();
+        assert_eq!(Path { cost: 5, nodes: expected }, path);
+        assert_eq!(None, dijkstra(start, steps, |&pos| pos == Vec2::new(3, 0)));
+
+        // A detour that is cheaper than the direct route.
+        let weighted = |&n: &u32| match n {
+            0 => vec![(1, 10), (2, 1)],
+            2 => vec![(3, 1)],
+            3 => vec![(1, 1)],
+            _ => vec![],
+        };
+        let path = dijkstra(0, weighted, |&n| n == 1).unwrap();
+        assert_eq!(Path { cost: 3, nodes: vec![0, 2, 3, 1] }, path);
+
+        // Unit steps can come straight from `Neighbors`.
+        let distances = bfs_distances(Vec2::new(0u8, 0), |pos| {
+            pos.neighbors(&Offset::ORTHOGONAL).filter(|pos| pos.x < 3 && pos.y < 3)
+        });
+        assert_eq!(Some(&4), distances.get(&Vec2::new(2, 2)));
+    }
+
+    #[test]
+    fn components() {
+        use crate::{
+            offsets::{Neighbors, Offset},
+            vecs::Vec2,
+        };
+
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!((4, 1), (sets.size(2), sets.size(5)));
+        assert_eq!(3, sets.count());
+
+        // Adjacency lists, with nodes 2 and 4 on their own.
+        let edges = [vec![1], vec![0, 3], vec![], vec![1], vec![4]];
+        let components = connected_components(0..edges.len(), |&n| edges[n].clone());
+        assert_eq!(vec![3, 1, 1], components.sizes);
+        assert_eq!(
+            [0, 0, 1, 0, 2].map(Some),
+            [0, 1, 2, 3, 4].map(|n| components.ids.get(&n).copied())
+        );
+
+        // Cells of a grid, in two regions.
+        let cells = [(0, 0), (1, 0), (1, 1), (3, 0), (3, 1)].map(Vec2::<u8>::from);
+        let components = connected_components(cells, |pos| pos.neighbors(&Offset::ORTHOGONAL));
+        assert_eq!(vec![3, 2], components.sizes);
+        assert_eq!(Some(&1), components.ids.get(&Vec2::new(3, 1)));
+    }
 }
@@ -0,0 +1,139 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{
+    offsets::{Neighbor, Neighbors, Offset},
+    vecs::Vec2,
+};
+
+type Pos = Vec2<usize>;
+
+/// A dense, rectangular grid of cells, stored row by row, such as parsed by
+/// `parsers::grid`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid out of its cells, row by row.
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len() % width == 0,
+            "{} cells don't form rows of {width}",
+            cells.len()
+        );
+        let height = cells.len() / width;
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_cells(width, vec![value; width * height])
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn size(&self) -> Pos {
+        Vec2::new(self.width, self.height)
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    fn cell_index(&self, pos: impl Into<Option<Pos>>) -> Option<usize> {
+        let pos = pos.into()?;
+        self.contains(pos).then(|| pos.x + pos.y * self.width)
+    }
+
+    /// Returns the cell at the position, or None outside of the grid. None is
+    /// outside of the grid as well, such that the result of
+    /// `Neighbor::neighbor` can be passed in directly.
+    pub fn get(&self, pos: impl Into<Option<Pos>>) -> Option<&T> {
+        self.cell_index(pos).map(|i| &self.cells[i])
+    }
+    pub fn get_mut(&mut self, pos: impl Into<Option<Pos>>) -> Option<&mut T> {
+        self.cell_index(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Returns the cell at the position, or `default` outside of the grid.
+    pub fn get_or(&self, pos: impl Into<Option<Pos>>, default: T) -> T
+    where
+        T: Copy,
+    {
+        self.get(pos).copied().unwrap_or(default)
+    }
+
+    /// Returns the position next to `pos` in the direction of `offset`, along
+    /// with its cell, if it is within the grid.
+    pub fn neighbor(&self, pos: Pos, offset: Offset) -> Option<(Pos, &T)> {
+        let pos = pos.neighbor(offset)?;
+        Some((pos, self.get(pos)?))
+    }
+
+    /// Iterates over the neighbors of `pos` within the grid, in the directions
+    /// of `offsets`, such as `Offset::ORTHOGONAL`.
+    pub fn neighbors<const N: usize>(
+        &self,
+        pos: Pos,
+        offsets: &'static [Offset; N],
+    ) -> impl Iterator<Item = (Pos, &T)> {
+        pos.neighbors(offsets)
+            .filter_map(|pos| Some((pos, self.get(pos)?)))
+    }
+
+    /// Iterates over all positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Vec2::new(x, y)))
+    }
+
+    /// Iterates over all cells along with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks_exact(self.width)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.cell_index(pos) {
+            Some(i) => &self.cells[i],
+            None => panic!("{pos} is outside of the grid of {}", self.size()),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        match self.cell_index(pos) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("{pos} is outside of the grid of {}", self.size()),
+        }
+    }
+}
@@ -1,65 +1,523 @@
-use crate::result::Result;
+use crate::{
+    error::Error,
+    result::Result,
+    throttle::{Clock, SystemClock, Throttle},
+};
 use std::{
+    collections::HashMap,
+    ffi::OsString,
     io::Read,
-    path::Path,
-    time::{Duration, Instant},
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
+/// Environment variable that takes precedence over any session key file.
+pub const SESSION_KEY_VAR: &str = "AOC_SESSION";
+const SESSION_KEY_PATH: &str = "./session_key.txt";
 const MINIMUM_TIME_BETWEEN_DOWNLOADS: Duration = Duration::from_secs(3);
+/// File that stores the time of the last download, so that the minimum time
+/// between downloads also applies across runs.
+const DOWNLOAD_TIMESTAMP_PATH: &str = "./download_timestamp.txt";
+const DOWNLOAD_RETRIES: u32 = 3;
+/// Time to wait before the first retry, doubled for every further retry.
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+/// Environment variable that overrides the directory inputs are cached in,
+/// the inputs of each year are stored in a subdirectory.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
 
-#[derive(Debug, Default)]
-pub struct Inputs {
-    session_key: Option<String>,
-    last_download_time: Option<Instant>,
+/// An input that replaces the cached input of a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+    Inline(Vec<u8>),
 }
 
-impl Inputs {
-    pub fn new() -> Inputs {
-        Default::default()
+impl InputSource {
+    /// Interprets `-` as standard input, and anything else as a file path.
+    pub fn from_arg(arg: String) -> InputSource {
+        if arg == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::File(arg.into())
+        }
     }
 
-    pub fn get(&mut self, day: u32) -> Result<Vec<u8>> {
-        let path = format!("./inputs/{day:0>2}.txt");
-        let path = Path::new(&path);
-        if let Ok(mut input) = std::fs::read(path) {
-            input.retain(|c| *c != b'\r');
-            return Ok(input);
+    fn read(&self) -> Result<Vec<u8>> {
+        Ok(match self {
+            InputSource::File(path) => std::fs::read(path)?,
+            InputSource::Stdin => {
+                let mut buf = Vec::new();
+                std::io::stdin().read_to_end(&mut buf)?;
+                buf
+            }
+            InputSource::Inline(input) => input.clone(),
+        })
+    }
+}
+
+/// A place inputs can be retrieved from.
+pub trait InputProvider: std::fmt::Debug {
+    /// Returns the input of a day, or `None` if this provider does not have it.
+    fn get(&mut self, day: u32) -> Result<Option<Vec<u8>>>;
+
+    /// Stores an input that was retrieved from another provider. Providers
+    /// that cannot store inputs ignore it.
+    fn store(&mut self, _day: u32, _input: &[u8]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Inputs stored as `NN.txt` files in a directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystemProvider {
+    directory: PathBuf,
+}
+
+impl FileSystemProvider {
+    pub fn new(directory: impl Into<PathBuf>) -> FileSystemProvider {
+        FileSystemProvider {
+            directory: directory.into(),
         }
+    }
 
-        let input = self.download(day)?;
-        std::fs::create_dir_all(path.parent().unwrap())?;
-        std::fs::write(path, &input)?;
-        Ok(input)
+    fn path(&self, day: u32) -> PathBuf {
+        self.directory.join(format!("{day:0>2}.txt"))
+    }
+}
+
+impl InputProvider for FileSystemProvider {
+    fn get(&mut self, day: u32) -> Result<Option<Vec<u8>>> {
+        match std::fs::read(self.path(day)) {
+            Ok(input) => Ok(Some(input)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn store(&mut self, day: u32, input: &[u8]) -> Result<()> {
+        std::fs::create_dir_all(&self.directory)?;
+        std::fs::write(self.path(day), input)?;
+        Ok(())
+    }
+}
+
+/// Looks up the session key, in order, in the `AOC_SESSION` environment
+/// variable, `./session_key.txt` and `$XDG_CONFIG_HOME/aoc/session`.
+pub fn find_session_key() -> Result<String> {
+    find_session_key_in(|name| std::env::var_os(name), Path::new(SESSION_KEY_PATH))
+}
+
+fn find_session_key_in(
+    var: impl Fn(&str) -> Option<OsString>,
+    local_path: &Path,
+) -> Result<String> {
+    if let Some(key) = var(SESSION_KEY_VAR).filter(|key| !key.is_empty()) {
+        return validate_session_key(&key.to_string_lossy(), SESSION_KEY_VAR);
+    }
+    let mut searched = vec![format!("${SESSION_KEY_VAR}")];
+
+    let config_dir = var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| Path::new(&home).join(".config")));
+    let config_path = config_dir.map(|dir| dir.join("aoc").join("session"));
+    for path in std::iter::once(local_path).chain(config_path.as_deref()) {
+        let location = path.display().to_string();
+        match std::fs::read_to_string(path) {
+            Ok(key) => return validate_session_key(&key, &location),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => searched.push(location),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(Error::SessionKeyNotFound(searched.join(", ")))
+}
+
+/// Trims the key, which must be a non-empty hexadecimal string.
+fn validate_session_key(key: &str, location: &str) -> Result<String> {
+    let key = key.trim();
+    if key.is_empty() || !key.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidSessionKey(location.to_owned()));
+    }
+    Ok(key.to_owned())
+}
+
+/// Inputs downloaded from the Advent of Code website, or a server that
+/// mimics it.
+#[derive(Debug)]
+pub struct HttpProvider {
+    year: u32,
+    base_url: String,
+    session_key: Option<String>,
+    throttle: Throttle,
+    retries: u32,
+    clock: Box<dyn Clock>,
+}
+
+impl HttpProvider {
+    pub fn new(year: u32) -> HttpProvider {
+        HttpProvider {
+            year,
+            base_url: "https://adventofcode.com".into(),
+            session_key: None,
+            throttle: Throttle::new(
+                MINIMUM_TIME_BETWEEN_DOWNLOADS,
+                Some(DOWNLOAD_TIMESTAMP_PATH.into()),
+            ),
+            retries: DOWNLOAD_RETRIES,
+            clock: Box::new(SystemClock),
+        }
+    }
+
+    /// Downloads from another server, which serves inputs at the same paths.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Uses the given session key, instead of looking it up with
+    /// [`find_session_key`].
+    pub fn with_session_key(mut self, session_key: impl Into<String>) -> Self {
+        self.session_key = Some(session_key.into());
+        self
+    }
+
+    /// Overrides the minimum time between two downloads.
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.throttle.set_min_interval(min_interval);
+        self
+    }
+
+    /// Overrides the file the time of the last download is stored in, with
+    /// `None` the throttle only applies within a single run.
+    pub fn with_timestamp_file(mut self, path: Option<PathBuf>) -> Self {
+        self.throttle = Throttle::new(self.throttle.min_interval(), path);
+        self
+    }
+
+    /// Overrides how often a download that fails with a server error is retried.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
     }
 
     fn get_session_key(&mut self) -> Result<&str> {
         if self.session_key.is_none() {
-            self.session_key = Some(std::fs::read_to_string("./session_key.txt")?);
+            self.session_key = Some(find_session_key()?);
         }
         Ok(self.session_key.as_ref().unwrap())
     }
+}
 
-    fn download(&mut self, day: u32) -> Result<Vec<u8>> {
-        let session_key = self.get_session_key()?;
-        let cookie_values = format!("session={session_key}");
+impl InputProvider for HttpProvider {
+    fn get(&mut self, day: u32) -> Result<Option<Vec<u8>>> {
+        let cookie_values = format!("session={}", self.get_session_key()?);
+        let url = format!(
+            "{}/{}/day/{day}/input",
+            self.base_url.trim_end_matches('/'),
+            self.year
+        );
 
-        let current_time = Instant::now();
-        if let Some(last_time) = self.last_download_time {
-            let delta_time = current_time - last_time;
-            if delta_time < MINIMUM_TIME_BETWEEN_DOWNLOADS {
-                std::thread::sleep(delta_time - MINIMUM_TIME_BETWEEN_DOWNLOADS);
+        let mut attempt = 0;
+        let resp = loop {
+            self.throttle.wait(&*self.clock)?;
+            let result = ureq::get(&url)
+                .set("cookie", &cookie_values)
+                .timeout(Duration::from_secs(5))
+                .call();
+            match result {
+                Err(ureq::Error::Status(500..=599, _)) if attempt < self.retries => {
+                    self.clock.sleep(RETRY_BACKOFF * 2u32.pow(attempt));
+                    attempt += 1;
+                }
+                result => break result?,
             }
-        }
-        self.last_download_time = Some(current_time);
-
-        let resp = ureq::get(&format!("https://adventofcode.com/2017/day/{day}/input"))
-            .set("cookie", &cookie_values)
-            .timeout(Duration::from_secs(5))
-            .call()
-            .map_err(Box::new)?;
+        };
 
         let mut buf = Vec::new();
         resp.into_reader().read_to_end(&mut buf)?;
-        Ok(buf)
+        Ok(Some(buf))
+    }
+}
+
+/// Inputs held in memory, mostly useful for tests.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InMemoryProvider {
+    inputs: HashMap<u32, Vec<u8>>,
+}
+
+impl InMemoryProvider {
+    pub fn new() -> InMemoryProvider {
+        Default::default()
+    }
+
+    pub fn with_input(mut self, day: u32, input: impl Into<Vec<u8>>) -> Self {
+        self.inputs.insert(day, input.into());
+        self
+    }
+}
+
+impl InputProvider for InMemoryProvider {
+    fn get(&mut self, day: u32) -> Result<Option<Vec<u8>>> {
+        Ok(self.inputs.get(&day).cloned())
+    }
+
+    fn store(&mut self, day: u32, input: &[u8]) -> Result<()> {
+        self.inputs.insert(day, input.to_vec());
+        Ok(())
+    }
+}
+
+/// Retrieves inputs from a cache, and fetches inputs missing from the cache
+/// from a remote provider.
+#[derive(Debug)]
+pub struct Inputs {
+    source: Option<InputSource>,
+    cache: Box<dyn InputProvider>,
+    remote: Option<Box<dyn InputProvider>>,
+    /// Whether the inputs are read from somewhere else than the default
+    /// cache, so they may not be the puzzle inputs.
+    is_overridden: bool,
+}
+
+impl Inputs {
+    /// Caches the inputs of the year in `./inputs/YEAR`, and downloads them
+    /// from the Advent of Code website.
+    /// The directory can be overridden with the `AOC_INPUT_DIR` environment
+    /// variable.
+    pub fn new(year: u32) -> Inputs {
+        Self::with_default_directory(year, std::env::var_os(INPUT_DIR_VAR))
+    }
+
+    fn with_default_directory(year: u32, directory: Option<OsString>) -> Inputs {
+        let is_overridden = directory.is_some();
+        let directory = directory.unwrap_or_else(|| "./inputs".into());
+        Inputs {
+            source: None,
+            cache: Box::new(FileSystemProvider::new(
+                Path::new(&directory).join(year.to_string()),
+            )),
+            remote: Some(Box::new(HttpProvider::new(year))),
+            is_overridden,
+        }
+    }
+
+    /// Overrides the directory inputs are read from, and downloaded into.
+    pub fn with_directory(self, directory: impl Into<PathBuf>) -> Self {
+        self.with_cache(FileSystemProvider::new(directory))
+    }
+
+    /// Uses the given source instead of the cached input. The cache is
+    /// neither read nor written.
+    pub fn with_source(mut self, source: InputSource) -> Self {
+        self.source = Some(source);
+        self.is_overridden = true;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: impl InputProvider + 'static) -> Self {
+        self.cache = Box::new(cache);
+        self.is_overridden = true;
+        self
+    }
+
+    pub fn with_remote(mut self, remote: impl InputProvider + 'static) -> Self {
+        self.remote = Some(Box::new(remote));
+        self
+    }
+
+    /// Only uses cached inputs, a missing input is an error instead of
+    /// being downloaded.
+    pub fn offline(mut self) -> Self {
+        self.remote = None;
+        self
+    }
+
+    /// Whether the inputs are the puzzle inputs in the default cache, which
+    /// the recorded answers apply to.
+    pub fn are_puzzle_inputs(&self) -> bool {
+        !self.is_overridden
+    }
+
+    pub fn get(&mut self, day: u32) -> Result<Vec<u8>> {
+        let mut input = match &self.source {
+            Some(source) => source.read()?,
+            None => match self.cache.get(day)? {
+                Some(input) => input,
+                None => {
+                    let remote = self.remote.as_mut().ok_or(Error::InputUnavailable(day))?;
+                    let input = remote.get(day)?.ok_or(Error::InputUnavailable(day))?;
+                    self.cache.store(day, &input)?;
+                    input
+                }
+            },
+        };
+        input.retain(|c| *c != b'\r');
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::throttle::test::FakeClock;
+
+    #[test]
+    fn sources() {
+        let directory = std::env::temp_dir().join(format!("aoc_inputs_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("03.txt"), b"1\r\n2\r\n").unwrap();
+        std::fs::write(directory.join("other.txt"), b"3\n").unwrap();
+
+        let mut inputs = Inputs::new(2017).with_directory(&directory);
+        assert_eq!(b"1\n2\n"[..], inputs.get(3).unwrap());
+        let mut inputs =
+            Inputs::new(2017).with_source(InputSource::File(directory.join("other.txt")));
+        assert_eq!(b"3\n"[..], inputs.get(3).unwrap());
+        let mut inputs = Inputs::new(2017).with_source(InputSource::Inline(b"4\r\n".to_vec()));
+        assert_eq!(b"4\n"[..], inputs.get(3).unwrap());
+        assert_eq!(InputSource::Stdin, InputSource::from_arg("-".into()));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn puzzle_inputs() {
+        assert!(Inputs::with_default_directory(2017, None).are_puzzle_inputs());
+        let inputs = Inputs::with_default_directory(2017, Some("tmp".into()));
+        assert!(!inputs.are_puzzle_inputs());
+        let inputs = Inputs::with_default_directory(2017, None).with_directory("tmp");
+        assert!(!inputs.are_puzzle_inputs());
+        let inputs = Inputs::with_default_directory(2017, None).with_source(InputSource::Stdin);
+        assert!(!inputs.are_puzzle_inputs());
+        let inputs = Inputs::with_default_directory(2017, None).offline();
+        assert!(inputs.are_puzzle_inputs());
+    }
+
+    #[test]
+    fn providers() {
+        let mut inputs = Inputs::new(2017)
+            .with_cache(InMemoryProvider::new().with_input(1, "1\n"))
+            .with_remote(InMemoryProvider::new().with_input(2, "2\r\n"));
+        assert_eq!(b"1\n"[..], inputs.get(1).unwrap());
+        assert_eq!(b"2\n"[..], inputs.get(2).unwrap());
+        assert!(matches!(inputs.get(3), Err(Error::InputUnavailable(3))));
+
+        let mut inputs = inputs.offline();
+        assert_eq!(b"2\n"[..], inputs.get(2).unwrap());
+        let mut inputs = Inputs::new(2017)
+            .with_cache(InMemoryProvider::new())
+            .offline();
+        assert!(matches!(inputs.get(1), Err(Error::InputUnavailable(1))));
+    }
+
+    /// Serves one response per connection, and returns the requests.
+    fn serve(
+        responses: Vec<(u16, &'static str)>,
+    ) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::{io::Write, net::TcpListener};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                requests.push(String::from_utf8(request).unwrap());
+            }
+            requests
+        });
+        (base_url, server)
+    }
+
+    fn http_provider(base_url: String, clock: &FakeClock) -> HttpProvider {
+        HttpProvider::new(2017)
+            .with_base_url(base_url)
+            .with_session_key("abc")
+            .with_timestamp_file(None)
+            .with_clock(clock.clone())
+    }
+
+    #[test]
+    fn download() {
+        let clock = FakeClock::new(1_000);
+        let (base_url, server) = serve(vec![(200, "1\n2\n"), (200, "3\n")]);
+        let mut provider = http_provider(base_url, &clock);
+        assert_eq!(Some(b"1\n2\n".to_vec()), provider.get(7).unwrap());
+        assert_eq!(Some(b"3\n".to_vec()), provider.get(8).unwrap());
+        assert_eq!(vec![MINIMUM_TIME_BETWEEN_DOWNLOADS], clock.take_sleeps());
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2017/day/7/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("cookie: session=abc\r\n"));
+        assert!(requests[1].starts_with("GET /2017/day/8/input HTTP/1.1\r\n"));
+    }
+
+    #[test]
+    fn retries() {
+        let secs = Duration::from_secs;
+        let clock = FakeClock::new(1_000);
+        let (base_url, server) = serve(vec![(503, ""), (500, ""), (200, "1\n")]);
+        let mut provider = http_provider(base_url, &clock).with_min_interval(Duration::ZERO);
+        assert_eq!(Some(b"1\n".to_vec()), provider.get(1).unwrap());
+        assert_eq!(vec![secs(1), secs(2)], clock.take_sleeps());
+        server.join().unwrap();
+
+        let (base_url, server) = serve(vec![(502, ""), (502, "")]);
+        let mut provider = http_provider(base_url, &clock).with_retries(1);
+        assert!(matches!(provider.get(1), Err(Error::NetworkError(_))));
+        server.join().unwrap();
+
+        let (base_url, server) = serve(vec![(404, "")]);
+        let mut provider = http_provider(base_url, &clock);
+        assert!(matches!(provider.get(1), Err(Error::NetworkError(_))));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn session_keys() {
+        let directory = std::env::temp_dir().join(format!("aoc_session_{}", std::process::id()));
+        let local_path = directory.join("session_key.txt");
+        std::fs::create_dir_all(directory.join("aoc")).unwrap();
+        let find = |session: Option<&str>| {
+            let var = |name: &str| match name {
+                SESSION_KEY_VAR => session.map(OsString::from),
+                "XDG_CONFIG_HOME" => Some(directory.clone().into()),
+                _ => None,
+            };
+            find_session_key_in(var, &local_path)
+        };
+
+        assert!(matches!(find(None), Err(Error::SessionKeyNotFound(_))));
+        std::fs::write(directory.join("aoc").join("session"), "abc\n").unwrap();
+        assert_eq!("abc", find(None).unwrap());
+        std::fs::write(&local_path, " 12ef\r\n").unwrap();
+        assert_eq!("12ef", find(None).unwrap());
+        assert_eq!("ff", find(Some("ff")).unwrap());
+        assert_eq!("12ef", find(Some("")).unwrap());
+        assert!(matches!(
+            find(Some("session=ff")),
+            Err(Error::InvalidSessionKey(_))
+        ));
+        std::fs::write(&local_path, "\n").unwrap();
+        assert!(matches!(find(None), Err(Error::InvalidSessionKey(_))));
+
+        std::fs::remove_dir_all(&directory).unwrap();
     }
 }
@@ -7,17 +7,28 @@
 #![feature(stmt_expr_attributes)]
 #![feature(trait_alias)]
 
+// Lets `#[derive(Parse)]` refer to the framework by name within it too.
+extern crate self as framework;
+
+pub mod answers;
 pub mod astr;
+pub mod cycle;
 pub mod error;
 pub mod graph;
+pub mod grid;
 pub mod inputs;
 pub mod iter;
 pub mod offsets;
+pub mod options;
 pub mod outputs;
 pub mod parsers;
 pub mod prelude;
+pub mod report;
 pub mod result;
 pub mod runner;
+pub mod selection;
+pub mod throttle;
+pub mod tree;
 pub mod util;
 pub mod vecs;
 
@@ -0,0 +1,133 @@
+use crate::{error::Error, inputs::InputSource, result::Result, selection::Selection};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Colored, human-readable output
+    #[default]
+    Pretty,
+    /// One JSON object per line, for every day and part
+    Json,
+    /// A header, followed by one row for every day and part
+    Csv,
+}
+
+impl Format {
+    fn from_arg(value: &str) -> Result<Format> {
+        match value {
+            "pretty" => Ok(Format::Pretty),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(Error::InvalidArgument(format!("unknown format {value}"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    pub format: Format,
+    pub selection: Selection,
+    /// Save answers for parts that do not have a recorded answer yet
+    pub record: bool,
+    /// Fail when any answer does not match its recorded answer
+    pub check: bool,
+    /// Run the days on multiple threads
+    pub parallel: bool,
+    /// Input to use instead of the cached input, for a single day
+    pub input: Option<InputSource>,
+    /// Directory to use instead of `./inputs/YEAR`
+    pub input_dir: Option<PathBuf>,
+    /// Never download inputs, missing inputs are an error
+    pub offline: bool,
+}
+
+impl Options {
+    /// Parses the command line arguments, excluding the program name.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, mut inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .take()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| Error::InvalidArgument(format!("{flag} requires a value")))
+            };
+            match flag {
+                "--format" => options.format = Format::from_arg(&value()?)?,
+                "--input" => options.input = Some(InputSource::from_arg(value()?)),
+                "--input-text" => options.input = Some(InputSource::Inline(value()?.into_bytes())),
+                "--input-dir" => options.input_dir = Some(value()?.into()),
+                "--record" => options.record = true,
+                "--check" => options.check = true,
+                "--parallel" => options.parallel = true,
+                "--offline" => options.offline = true,
+                "--skip-slow" => options.selection.skip_slow = true,
+                _ => options.selection.add(&arg)?,
+            }
+            if inline_value.is_some() {
+                return Err(Error::InvalidArgument(format!(
+                    "{flag} does not take a value"
+                )));
+            }
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options> {
+        Options::from_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(Format::Pretty, parse(&[]).unwrap().format);
+        assert_eq!(Format::Json, parse(&["--format", "json"]).unwrap().format);
+        assert_eq!(Format::Csv, parse(&["--format=csv"]).unwrap().format);
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--format"]).is_err());
+    }
+
+    #[test]
+    fn days() {
+        let options = parse(&["3", "--format", "json", "12.1"]).unwrap();
+        assert!(options.selection.includes_day(3, 2, false));
+        assert!(options.selection.includes_part(12, 1, false));
+        assert!(!options.selection.includes_part(12, 2, false));
+        assert!(!options.selection.includes_day(4, 2, false));
+        assert!(parse(&[]).unwrap().selection.includes_day(4, 2, false));
+        assert!(parse(&["--unknown"]).is_err());
+        assert!(parse(&["3x"]).is_err());
+    }
+
+    #[test]
+    fn flags() {
+        let options = parse(&["--check"]).unwrap();
+        assert!(options.check && !options.record);
+        let options = parse(&["--record", "5"]).unwrap();
+        assert!(!options.check && options.record);
+        assert!(parse(&["--parallel"]).unwrap().parallel);
+        assert!(parse(&["--offline"]).unwrap().offline);
+        assert!(parse(&["--check=yes"]).is_err());
+    }
+
+    #[test]
+    fn inputs() {
+        let options = parse(&["--input", "-", "--input-dir=tmp"]).unwrap();
+        assert_eq!(Some(InputSource::Stdin), options.input);
+        assert_eq!(Some(PathBuf::from("tmp")), options.input_dir);
+        let options = parse(&["--input=a.txt"]).unwrap();
+        assert_eq!(Some(InputSource::File("a.txt".into())), options.input);
+        let options = parse(&["--input-text", "1,2"]).unwrap();
+        assert_eq!(Some(InputSource::Inline(b"1,2".to_vec())), options.input);
+        assert!(parse(&["--input"]).is_err());
+    }
+}
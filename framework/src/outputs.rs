@@ -8,6 +8,7 @@ use std::{
 #[derive(Debug)]
 pub struct ColoredOutput {
     value: String,
+    plain: String,
     control_count: usize,
 }
 
@@ -15,6 +16,10 @@ impl ColoredOutput {
     pub fn value(&self) -> &str {
         &self.value
     }
+    /// The value without any color codes, for machine-readable output
+    pub fn plain(&self) -> &str {
+        &self.plain
+    }
     pub fn control_count(&self) -> usize {
         self.control_count
     }
@@ -41,11 +46,12 @@ impl<T: fmt::Display + NotIntoColorOutput> From<T> for ColoredOutput {
 
 impl<'s> From<&'s str> for ColoredOutput {
     fn from(value: &'s str) -> Self {
-        let before_style_len = value.len();
+        let plain = value.to_owned();
         let value = value.white().bold().to_string();
-        let control_count = value.len() - before_style_len;
+        let control_count = value.len() - plain.len();
         ColoredOutput {
             value,
+            plain,
             control_count,
         }
     }
@@ -82,30 +88,31 @@ macro_rules! impl_binary_op_output {
         {
             fn from(input: $struct_name<T>) -> Self {
                 let mut value = String::new();
-                let mut str_len = 0;
+                let mut plain = String::new();
                 let mut acc: I = num::$identity_fn();
                 for (i, v) in input.0.into_iter().enumerate() {
                     let s = v.to_string();
                     if i != 0 {
                         _ = write!(value, "{} ", $symbol.bright_magenta());
-                        str_len += 2;
+                        _ = write!(plain, "{} ", $symbol);
                     }
                     _ = write!(value, "{} ", s.white());
+                    _ = write!(plain, "{} ", s);
                     acc = acc.$trait_fn(v);
-                    str_len += s.len() + 1;
                 }
 
-                if str_len != 0 {
+                if !plain.is_empty() {
                     _ = write!(value, "{} ", "=".bright_magenta());
-                    str_len += 2;
+                    plain.push_str("= ");
                 }
 
                 let s = acc.to_string();
                 _ = write!(value, "{}", s.white().bold());
-                str_len += s.len();
-                let control_count = value.len() - str_len;
+                plain.push_str(&s);
+                let control_count = value.len() - plain.len();
                 ColoredOutput {
                     value,
+                    plain,
                     control_count,
                 }
             }
@@ -14,7 +14,14 @@ pub trait ParserCombiExt<'s>: Sized + Parser<'s> {
         Trailed(self, parser)
     }
 
-    /// Attempts the first parser, and upon failure attempts the second parser
+    /// Skips the spaces and tabs after this parser, see `ws`.
+    fn lexeme(self) -> Trailed<Self, common::Ws> {
+        Trailed(self, ws())
+    }
+
+    /// Attempts the first parser, and upon failure attempts the second parser.
+    /// When both fail, reports the failure that got furthest into the input,
+    /// or what either of them expected if they failed at the same point.
     fn or<P2: Parser<'s, Output = Self::Output>>(self, parser: P2) -> Or<Self, P2> {
         Or(self, parser)
     }
@@ -28,6 +35,12 @@ pub trait ParserCombiExt<'s>: Sized + Parser<'s> {
         MapRes(self, f)
     }
 
+    /// Labels failures of this parser with the part of the grammar it parses,
+    /// such that errors read like "in particle > in velocity > expected ...".
+    fn context(self, label: &'static str) -> Context<Self> {
+        Context(self, label)
+    }
+
     /// Attempts to apply this parser, upon success, wraps the value in Some,
     /// upon failure, succeeds with value None and no input consumed.
     fn opt(self) -> Opt<Self> {
@@ -50,6 +63,9 @@ pub struct Map<P, F>(P, F);
 #[derive(Debug, Clone, Copy)]
 pub struct MapRes<P, F>(P, F);
 
+#[derive(Debug, Clone, Copy)]
+pub struct Context<P>(P, &'static str);
+
 #[derive(Debug, Clone, Copy)]
 pub struct Opt<P>(P);
 
@@ -88,7 +104,22 @@ impl<'s, P1: Parser<'s>, P2: Parser<'s, Output = P1::Output>> Parser<'s> for Or<
     type Output = P1::Output;
 
     fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
-        self.0.parse(input).or_else(|_| self.1.parse(input))
+        let (e1, r1) = match self.0.parse(input) {
+            Ok(result) => return Ok(result),
+            Err(error) => error,
+        };
+        let (e2, r2) = match self.1.parse(input) {
+            Ok(result) => {
+                error::note_failure((e1, r1));
+                return Ok(result);
+            }
+            Err(error) => error,
+        };
+        Err(match r1.len().cmp(&r2.len()) {
+            std::cmp::Ordering::Less => (e1, r1),
+            std::cmp::Ordering::Equal => (e1.merge(e2), r2),
+            std::cmp::Ordering::Greater => (e2, r2),
+        })
     }
 }
 
@@ -106,12 +137,31 @@ impl<'s, P: Parser<'s>, T: 's, F: Fn(P::Output) -> Result<T, ParseError>> Parser
     type Output = T;
 
     fn parse(&self, input: &'s [u8]) -> ParseResult<'s, T> {
-        self.0
-            .parse(input)
-            .and_then(|(value, remainder)| match (self.1)(value) {
-                Ok(value) => Ok((value, remainder)),
-                Err(err) => Err((err, input)),
-            })
+        let snapshot = error::snapshot_failures();
+        let (value, remainder) = self.0.parse(input)?;
+        match (self.1)(value) {
+            Ok(value) => Ok((value, remainder)),
+            Err(err) => {
+                // What was recovered from within the rejected value doesn't
+                // explain why it was rejected.
+                error::restore_failures(snapshot);
+                Err((err, input))
+            }
+        }
+    }
+}
+
+impl<'s, P: Parser<'s>> Parser<'s> for Context<P> {
+    type Output = P::Output;
+
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
+        self.0.parse(input).map_err(|(error, remainder)| {
+            if error::is_describing() {
+                (ParseError::Context(self.1, Box::new(error)), remainder)
+            } else {
+                (error, remainder)
+            }
+        })
     }
 }
 
@@ -121,7 +171,10 @@ impl<'s, P: Parser<'s>> Parser<'s> for Opt<P> {
     fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
         Ok(match self.0.parse(input) {
             Ok((value, remainder)) => (Some(value), remainder),
-            _ => (None, input),
+            Err(failure) => {
+                error::note_failure(failure);
+                (None, input)
+            }
         })
     }
 }
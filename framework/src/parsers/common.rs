@@ -25,9 +25,13 @@ pub macro pattern($p:pat) {{
 
         fn parse(&self, input: &'s [u8]) -> $crate::parsers::ParseResult<'s, Self::Output> {
             match input.first().cloned() {
-                None => Err((ParseError::EmptyInput, input)),
                 Some(v @ $p) => Ok((v, &input[1..])),
-                Some(_) => Err((ParseError::UnexpectedChar, input)),
+                _ => Err((
+                    ParseError::expected(|| {
+                        [$crate::parsers::Expectation::Pattern(stringify!($p))]
+                    }),
+                    input,
+                )),
             }
         }
     }
@@ -49,7 +53,7 @@ impl<'s> Parser<'s> for Token<u8> {
                 return Ok(((), &input[1..]));
             }
         }
-        Err((ParseError::TokenDoesNotMatch, input))
+        Err((token_error(&[self.value]), input))
     }
 }
 
@@ -61,7 +65,7 @@ impl<'s, T: 's + Clone> Parser<'s> for Token<(u8, T)> {
                 return Ok((self.value.1.clone(), &input[1..]));
             }
         }
-        Err((ParseError::TokenDoesNotMatch, input))
+        Err((token_error(&[self.value.0]), input))
     }
 }
 
@@ -71,7 +75,7 @@ impl<'s, 't: 's> Parser<'s> for Token<&'t [u8]> {
         if input.starts_with(self.value) {
             Ok(((), &input[self.value.len()..]))
         } else {
-            Err((ParseError::TokenDoesNotMatch, input))
+            Err((token_error(self.value), input))
         }
     }
 }
@@ -82,7 +86,7 @@ impl<'s, 't: 's, T: 's + Clone> Parser<'s> for Token<(&'t [u8], T)> {
         if input.starts_with(self.value.0) {
             Ok((self.value.1.clone(), &input[self.value.0.len()..]))
         } else {
-            Err((ParseError::TokenDoesNotMatch, input))
+            Err((token_error(self.value.0), input))
         }
     }
 }
@@ -93,7 +97,7 @@ impl<'s, 't: 's, const N: usize> Parser<'s> for Token<&'t [u8; N]> {
         if input.starts_with(self.value) {
             Ok(((), &input[self.value.len()..]))
         } else {
-            Err((ParseError::TokenDoesNotMatch, input))
+            Err((token_error(self.value), input))
         }
     }
 }
@@ -104,15 +108,88 @@ impl<'s, 't: 's, T: 's + Clone, const N: usize> Parser<'s> for Token<(&'t [u8; N
         if input.starts_with(self.value.0) {
             Ok((self.value.1.clone(), &input[self.value.0.len()..]))
         } else {
-            Err((ParseError::TokenDoesNotMatch, input))
+            Err((token_error(self.value.0), input))
         }
     }
 }
 
+#[inline]
+fn token_error(token: &[u8]) -> ParseError {
+    ParseError::expected(|| [Expectation::Token(token.to_vec())])
+}
+
 pub fn token<T>(token: T) -> Token<T> {
     Token { value: token }
 }
 
+fn is_space(c: u8) -> bool {
+    matches!(c, b' ' | b'\t')
+}
+
+fn skip_spaces(input: &[u8]) -> &[u8] {
+    let len = input.iter().take_while(|&&c| is_space(c)).count();
+    &input[len..]
+}
+
+/// Skips any amount of spaces and tabs, including none, see `ws`.
+#[derive(Debug, Clone, Copy)]
+pub struct Ws;
+impl<'s> Parser<'s> for Ws {
+    type Output = ();
+
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, ()> {
+        Ok(((), skip_spaces(input)))
+    }
+}
+/// Skips any amount of spaces and tabs, including none. Line endings are not
+/// skipped, as they usually separate the items of an input.
+pub fn ws() -> Ws {
+    Ws
+}
+
+/// Matches text like `token`, except that whitespace is flexible, see
+/// `phrase`.
+#[derive(Debug, Clone, Copy)]
+pub struct Phrase<'t>(&'t [u8]);
+impl<'s, 't: 's> Parser<'s> for Phrase<'t> {
+    type Output = ();
+
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, ()> {
+        let mut text = self.0;
+        let mut remainder = input;
+        while let Some(&c) = text.first() {
+            if is_space(c) || c == b'\n' {
+                let len = text
+                    .iter()
+                    .take_while(|&&c| is_space(c) || c == b'\n')
+                    .count();
+                remainder = skip_spaces(remainder);
+                for _ in text[..len].iter().filter(|&&c| c == b'\n') {
+                    match remainder.strip_prefix(b"\n") {
+                        Some(rest) => remainder = skip_spaces(rest),
+                        None => return Err((token_error(self.0), input)),
+                    }
+                }
+                text = &text[len..];
+            } else {
+                match remainder.strip_prefix(&[c]) {
+                    Some(rest) => remainder = rest,
+                    None => return Err((token_error(self.0), input)),
+                }
+                text = &text[1..];
+            }
+        }
+        Ok(((), remainder))
+    }
+}
+/// Matches text like `token`, except that spaces and tabs in the text match
+/// any amount of spaces and tabs, including none, and a line ending in the
+/// text matches a line ending with trailing spaces and indentation. This way
+/// `phrase(b":\n  - Write ")` also accepts re-indented input.
+pub fn phrase(text: &[u8]) -> Phrase<'_> {
+    Phrase(text)
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Any;
 impl<'s> Parser<'s> for Any {
@@ -158,4 +235,87 @@ mod test {
         assert_eq!(Err((ParseError::Overflow,      &b"-129"[..])), number::<i8>().parse(b"-129" ));
         assert_eq!(Err((ParseError::EmptyInput,    &b""    [..])), number::<i8>().parse(b""     ));
     }
+
+    #[test]
+    #[rustfmt::skip]
+    fn radix_numbers() {
+        assert_eq!( Ok((255,                       &b"g"   [..])), radix_number::<u8>(16).parse(b"fFg"  ));
+        assert_eq!( Ok((5,                         &b"2"   [..])), radix_number::<u8>(2).parse(b"1012"  ));
+        assert_eq!( Ok((-35,                       &b""    [..])), radix_number::<i8>(36).parse(b"-z"   ));
+        assert_eq!(Err((ParseError::Overflow,      &b"100" [..])), radix_number::<u8>(16).parse(b"100"  ));
+        assert_eq!(Err((ParseError::ExpectedDigit, &b"g"   [..])), radix_number::<u8>(16).parse(b"g"    ));
+
+        assert_eq!( Ok((31,                        &b""    [..])), prefixed_number::<u8>().parse(b"0x1f"));
+        assert_eq!( Ok((-31,                       &b""    [..])), prefixed_number::<i8>().parse(b"-0x1F"));
+        assert_eq!( Ok((8,                         &b""    [..])), prefixed_number::<u8>().parse(b"0o10"));
+        assert_eq!( Ok((-128,                      &b""    [..])), prefixed_number::<i8>().parse(b"-0b10000000"));
+        assert_eq!( Ok((10,                        &b""    [..])), prefixed_number::<u8>().parse(b"10"  ));
+        assert_eq!( Ok((0,                         &b"y"   [..])), prefixed_number::<u8>().parse(b"0y"  ));
+        assert_eq!(Err((ParseError::Overflow,      &b"0x80"[..])), prefixed_number::<i8>().parse(b"0x80"));
+        assert_eq!(Err((ParseError::ExpectedDigit, &b"2"   [..])), prefixed_number::<u8>().parse(b"0b2" ));
+
+        // A knot hash, as written by day 10.
+        let hash = b"a2582a3a0e66e6e86e3812dcb672a272";
+        let expected = 0xa2582a3a0e66e6e86e3812dcb672a272;
+        assert_eq!(Ok(expected), radix_number::<u128>(16).execute(hash).map_err(|e| e.to_string()));
+        assert!(radix_number::<u64>(16).execute(hash).is_err());
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn float_numbers() {
+        assert_eq!( Ok((1.5,                       &b""    [..])), number::<f64>().parse(b"1.5"   ));
+        assert_eq!( Ok((-1500.0,                   &b""    [..])), number::<f64>().parse(b"-1.5e3"));
+        assert_eq!( Ok((0.25,                      &b""    [..])), number::<f32>().parse(b"+25E-2"));
+        assert_eq!( Ok((3.0,                       &b"."   [..])), number::<f64>().parse(b"3."    ));
+        assert_eq!( Ok((3.0,                       &b"e"   [..])), number::<f64>().parse(b"3e"    ));
+        assert_eq!( Ok((2.0,                       &b",1"  [..])), number::<f64>().parse(b"2,1"   ));
+        assert_eq!(Err((ParseError::Overflow,      &b"1e39"[..])), number::<f32>().parse(b"1e39"  ));
+        assert_eq!(Err((ParseError::ExpectedDigit, &b".5"  [..])), number::<f64>().parse(b".5"    ));
+        assert_eq!(Err((ParseError::EmptyInput,    &b""    [..])), number::<f64>().parse(b"-"     ));
+    }
+
+    #[test]
+    #[cfg(feature = "bigint")]
+    fn big_numbers() {
+        use num::{BigInt, BigUint};
+
+        let big = |text: &str| text.parse::<BigInt>().unwrap();
+        let digits = "123456789012345678901234567890123456789012345678901234567890";
+        let (x, remainder) = number::<BigInt>().parse(digits.as_bytes()).unwrap();
+        assert_eq!((big(digits), &b""[..]), (x, remainder));
+        let negative = format!("-{digits}x");
+        let (x, remainder) = number::<BigInt>().parse(negative.as_bytes()).unwrap();
+        assert_eq!((-big(digits), &b"x"[..]), (x, remainder));
+
+        let hash = prefixed_number::<BigUint>().parse(b"0xa2582a3a0e66e6e86e3812dcb672a272");
+        assert_eq!(
+            Ok(BigUint::from(0xa2582a3a0e66e6e86e3812dcb672a272_u128)),
+            hash.map(|(x, _)| x)
+        );
+        assert_eq!(
+            Err((ParseError::ExpectedDigit, &b"x"[..])),
+            number::<BigUint>().parse(b"x")
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn whitespace() {
+        let arrow = phrase(b" -> ");
+        assert_eq!(Ok(((), &b"b"    [..])), arrow.parse(b" -> b"   ));
+        assert_eq!(Ok(((), &b"b"    [..])), arrow.parse(b"->b"     ));
+        assert_eq!(Ok(((), &b"b"    [..])), arrow.parse(b"\t->  b" ));
+        assert_eq!(Ok(((), &b"\nb"  [..])), arrow.parse(b"->\nb"   ));
+        assert!(arrow.parse(b"- >b").is_err());
+
+        let indented = phrase(b":\n  - ");
+        assert_eq!(Ok(((), &b"x"    [..])), indented.parse(b":\n  - x"   ));
+        assert_eq!(Ok(((), &b"x"    [..])), indented.parse(b": \n\t-x"  ));
+        assert!(indented.parse(b": - x").is_err());
+
+        let word = take_while((), |_, c| c.is_ascii_lowercase()).lexeme();
+        assert_eq!(Ok((&b"ab"[..],  &b"cd"  [..])), word.parse(b"ab  cd"  ));
+        assert_eq!(Ok((&b"ab"[..],  &b"\n"  [..])), word.parse(b"ab \n"   ));
+    }
 }
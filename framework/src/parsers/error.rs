@@ -1,23 +1,406 @@
+use std::{borrow::Cow, cell::Cell, fmt};
 use thiserror::Error;
 
 pub type ParseResult<'s, T> = Result<(T, &'s [u8]), (ParseError, &'s [u8])>;
 
-#[derive(Error, Debug, PartialEq, Eq)]
+/// A failure, with the length of the input that remained at that point.
+pub(crate) type Failure = (ParseError, usize);
+
+thread_local! {
+    /// Whether failing parsers describe what they expected. Describing
+    /// allocates, which slows down parsers that try many alternatives, so
+    /// parsers only describe when `Execute` parses again after a failure.
+    static DESCRIBE_EXPECTED: Cell<bool> = const { Cell::new(false) };
+
+    /// The furthest failure that a combinator recovered from, such as the
+    /// element after the last separator of `sep_by`. `None` when not tracked.
+    static RECOVERED_FAILURE: Cell<Option<Option<Failure>>> = const { Cell::new(None) };
+}
+
+/// Restores the diagnostics state of an enclosing parse when dropped.
+struct Restore(bool, Option<Option<Failure>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        DESCRIBE_EXPECTED.set(self.0);
+        RECOVERED_FAILURE.set(self.1.take());
+    }
+}
+
+/// Evaluates `f` with parsers not describing what they expected.
+pub(crate) fn without_descriptions<R>(f: impl FnOnce() -> R) -> R {
+    let recovered = RECOVERED_FAILURE.take();
+    let _restore = Restore(DESCRIBE_EXPECTED.replace(false), recovered);
+    f()
+}
+
+/// Evaluates `f` with parsers describing what they expected, and returns the
+/// furthest failure that was recovered from along the way.
+pub(crate) fn tracking_failures<R>(f: impl FnOnce() -> R) -> (R, Option<Failure>) {
+    // Start tracking, with `Some(None)` as no failure was recovered from yet.
+    let recovered = RECOVERED_FAILURE.replace(Some(None));
+    let _restore = Restore(DESCRIBE_EXPECTED.replace(true), recovered);
+    let result = f();
+    let recovered = RECOVERED_FAILURE.take().flatten();
+    (result, recovered)
+}
+
+/// The furthest failure recovered from so far, while describing, to go back
+/// to with `restore_failures`.
+pub(crate) struct FailureSnapshot(Option<Option<Failure>>);
+
+#[inline]
+pub(crate) fn snapshot_failures() -> FailureSnapshot {
+    if !DESCRIBE_EXPECTED.get() {
+        return FailureSnapshot(None);
+    }
+    let recovered = RECOVERED_FAILURE.take();
+    RECOVERED_FAILURE.set(recovered.clone());
+    FailureSnapshot(recovered)
+}
+
+/// Forgets the failures recovered from since the snapshot was taken, such as
+/// when they are part of input that was rejected as a whole.
+#[inline]
+pub(crate) fn restore_failures(snapshot: FailureSnapshot) {
+    if DESCRIBE_EXPECTED.get() {
+        RECOVERED_FAILURE.set(snapshot.0);
+    }
+}
+
+#[inline]
+pub(crate) fn is_describing() -> bool {
+    DESCRIBE_EXPECTED.get()
+}
+
+/// Remembers a failure that a combinator recovered from. If parsing stops
+/// early, the furthest of these failures is likely the reason.
+#[inline]
+pub(crate) fn note_failure(failure: (ParseError, &[u8])) {
+    if DESCRIBE_EXPECTED.get() {
+        note_failure_slow(failure);
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn note_failure_slow((error, remainder): (ParseError, &[u8])) {
+    let Some(recovered) = RECOVERED_FAILURE.take() else {
+        return;
+    };
+    RECOVERED_FAILURE.set(Some(Some(match recovered {
+        Some((other, len)) if len < remainder.len() => (other, len),
+        Some((other, len)) if len == remainder.len() => (other.merge(error), len),
+        _ => (error, remainder.len()),
+    })));
+}
+
+/// Maximum number of characters of the offending line shown in an error.
+const SNIPPET_WIDTH: usize = 80;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
-    #[error("input not fully consumed, remainder: {0:?}")]
-    InputNotConsumed(String),
-    #[error("{0}, remainder: {1:?}")]
-    WithRemainder(Box<ParseError>, String),
+    #[error("input not fully consumed at {0}")]
+    InputNotConsumed(Position),
+    #[error("{0} at {1}")]
+    WithPosition(Box<ParseError>, Position),
     #[error("empty input")]
     EmptyInput,
     #[error("expected a digit")]
     ExpectedDigit,
     #[error("overflow")]
     Overflow,
-    #[error("token does not match")]
-    TokenDoesNotMatch,
+    #[error("expected {}", describe_expected(.0))]
+    Expected(Vec<Expectation>),
     #[error("unexpected char")]
     UnexpectedChar,
     #[error("{0}")]
-    Custom(&'static str),
+    Custom(Cow<'static, str>),
+    #[error("in {0} > {1}")]
+    Context(&'static str, Box<ParseError>),
+}
+
+/// Something a parser would have accepted, used to describe a failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    /// A literal byte string, as matched by `token`.
+    Token(Vec<u8>),
+    /// A byte matching a pattern, as matched by `pattern!`.
+    Pattern(&'static str),
+    /// Anything else, described in words.
+    Description(&'static str),
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expectation::Token(token) => write!(f, "`{}`", token.escape_ascii()),
+            Expectation::Pattern(pattern) => write!(f, "`{pattern}`"),
+            Expectation::Description(description) => f.write_str(description),
+        }
+    }
+}
+
+#[inline]
+fn describe<I, F>(expectations: F) -> Vec<Expectation>
+where
+    I: IntoIterator<Item = Expectation>,
+    F: FnOnce() -> I,
+{
+    #[cold]
+    #[inline(never)]
+    fn collect<I: IntoIterator<Item = Expectation>>(
+        expectations: impl FnOnce() -> I,
+    ) -> Vec<Expectation> {
+        expectations().into_iter().collect()
+    }
+
+    if DESCRIBE_EXPECTED.get() {
+        collect(expectations)
+    } else {
+        Vec::new()
+    }
+}
+
+fn describe_expected(expectations: &[Expectation]) -> String {
+    let list = expectations
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    match expectations.len() {
+        0 => "something else".to_owned(),
+        1 => list,
+        _ => format!("one of {list}"),
+    }
+}
+
+impl ParseError {
+    pub fn custom(message: impl Into<Cow<'static, str>>) -> ParseError {
+        ParseError::Custom(message.into())
+    }
+
+    /// A failure where any of the expectations would have been accepted. The
+    /// expectations are only built when they are going to be reported.
+    pub fn expected<I, F>(expectations: F) -> ParseError
+    where
+        I: IntoIterator<Item = Expectation>,
+        F: FnOnce() -> I,
+    {
+        ParseError::Expected(describe(expectations))
+    }
+
+    /// Combines the errors of two alternatives that failed at the same point.
+    /// When both describe what they expected, the result expects either,
+    /// otherwise the latter error is kept.
+    #[inline]
+    pub fn merge(self, other: ParseError) -> ParseError {
+        match (self, other) {
+            // Nothing to merge when not describing.
+            (ParseError::Expected(a), ParseError::Expected(b)) if a.is_empty() && b.is_empty() => {
+                ParseError::Expected(a)
+            }
+            (a, b) => a.merge_expectations(b),
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn merge_expectations(self, other: ParseError) -> ParseError {
+        match (self.into_expectations(), other.into_expectations()) {
+            (Ok(mut expectations), Ok(others)) => {
+                for expectation in others {
+                    if !expectations.contains(&expectation) {
+                        expectations.push(expectation);
+                    }
+                }
+                ParseError::Expected(expectations)
+            }
+            (_, Ok(others)) => ParseError::Expected(others),
+            (_, Err(other)) => other,
+        }
+    }
+
+    fn into_expectations(self) -> Result<Vec<Expectation>, ParseError> {
+        match self {
+            ParseError::Expected(expectations) => Ok(expectations),
+            ParseError::ExpectedDigit => Ok(describe(|| [Expectation::Description("a digit")])),
+            e => Err(e),
+        }
+    }
+}
+
+/// Location of a parse failure in the original input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    /// One-based line number.
+    pub line: usize,
+    /// One-based column, in characters.
+    pub column: usize,
+    /// The line containing the failure, without its line ending. `None` when
+    /// the line is no longer known, such as when a stream has discarded its
+    /// start.
+    pub snippet: Option<String>,
+}
+
+impl Position {
+    /// Locates the remainder of a parser in the input it was given, the
+    /// remainder is expected to be a suffix of the input.
+    pub fn new(input: &[u8], remainder: &[u8]) -> Position {
+        let offset = input.len().saturating_sub(remainder.len());
+        let line_start = input[..offset]
+            .iter()
+            .rposition(|&c| c == b'\n')
+            .map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .iter()
+            .position(|&c| c == b'\n')
+            .map_or(input.len(), |i| offset + i);
+        let line = input[..line_start].iter().filter(|&&c| c == b'\n').count() + 1;
+        let column = String::from_utf8_lossy(&input[line_start..offset])
+            .chars()
+            .count()
+            + 1;
+        Position {
+            offset,
+            line,
+            column,
+            snippet: Some(String::from_utf8_lossy(&input[line_start..line_end]).into_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    /// Shows the line and column, followed by the offending line with a caret
+    /// under the failure. Long lines are cut to the part around the failure.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(snippet) = &self.snippet else {
+            return write!(f, "line {}, column {}", self.line, self.column);
+        };
+        let chars = snippet.chars().collect::<Vec<_>>();
+        let index = self.column - 1;
+        let start = index
+            .saturating_sub(SNIPPET_WIDTH / 2)
+            .min(chars.len().saturating_sub(SNIPPET_WIDTH));
+        let end = (start + SNIPPET_WIDTH).min(chars.len());
+        let (prefix, suffix) = (
+            if start > 0 { "…" } else { "" },
+            if end < chars.len() { "…" } else { "" },
+        );
+
+        let line = chars[start..end].iter().collect::<String>();
+        let indent = chars[start..index.min(chars.len())]
+            .iter()
+            .map(|&c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let indent_prefix = if start > 0 { " " } else { "" };
+        write!(
+            f,
+            "line {}, column {}:\n{prefix}{line}{suffix}\n{indent_prefix}{indent}^",
+            self.line, self.column
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parsers::*;
+
+    #[test]
+    fn positions() {
+        let input = b"a 1\nb 2\nc x\n";
+        let position = Position::new(input, &input[10..]);
+        assert_eq!(
+            (10, 3, 3),
+            (position.offset, position.line, position.column)
+        );
+        assert_eq!("line 3, column 3:\nc x\n  ^", position.to_string());
+
+        let position = Position::new(input, b"");
+        assert_eq!((4, 1), (position.line, position.column));
+        assert_eq!("line 4, column 1:\n\n^", position.to_string());
+
+        let position = Position::new(b"\tx", b"x");
+        assert_eq!("line 1, column 2:\n\tx\n\t^", position.to_string());
+
+        let input = [b'1'; 200];
+        let position = Position::new(&input, &input[150..]);
+        let expected = format!(
+            "line 1, column 151:\n…{}…\n{}^",
+            "1".repeat(80),
+            " ".repeat(41)
+        );
+        assert_eq!(expected, position.to_string());
+    }
+
+    /// Parses with descriptions, like `Execute` does after a failure.
+    fn describe<'s, P: Parser<'s>>(parser: &P, input: &'s [u8]) -> (ParseError, &'s [u8]) {
+        tracking_failures(|| parser.parse(input)).0.err().unwrap()
+    }
+
+    #[test]
+    fn expectations() {
+        let dir = token(b"se")
+            .or(token(b"ne"))
+            .or(token(b'\n'))
+            .or(pattern!(b'a'..=b'c').map(|_| ()));
+        let error = describe(&dir, b"x").0;
+        assert_eq!(
+            "expected one of `se`, `ne`, `\\n`, `b'a'..=b'c'`",
+            error.to_string()
+        );
+
+        // Only the alternative that got furthest is reported.
+        let dir = token(b"se").or(token(b"n").then(token(b"e").or(token(b"w"))));
+        let (error, remainder) = describe(&dir, b"nx");
+        assert_eq!(
+            (b"x".as_slice(), "expected one of `e`, `w`"),
+            (remainder, &*error.to_string())
+        );
+
+        let number = token(b"-").then(number::<u32>()).or(number::<u32>());
+        let error = describe(&number, b"x").0;
+        assert_eq!("expected one of `-`, a digit", error.to_string());
+    }
+
+    #[test]
+    fn rejections() {
+        // A value rejected as a whole is reported, rather than what was tried
+        // after its end.
+        let even = number::<u32>()
+            .and(token(b'-').opt())
+            .map_res(|(n, _)| match n % 2 {
+                0 => Ok(n),
+                _ => Err(ParseError::custom("odd")),
+            });
+        let error = even.execute(b"3").unwrap_err();
+        assert_eq!(
+            "parse error: odd at line 1, column 1:\n3\n^",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn contexts() {
+        let pair = number::<u32>()
+            .context("x")
+            .and(token(b',').then(number::<u32>()).context("y"));
+        let pairs = pair.context("pair").sep_by::<_, Vec<_>>(token(b'\n'));
+        assert_eq!(
+            Ok(vec![(1, 2), (3, 4)]),
+            pairs.execute(b"1,2\n3,4\n").map_err(|e| e.to_string())
+        );
+
+        // The failing element is reported, rather than where the separated
+        // elements end.
+        let error = pairs.execute(b"1,2\n3,4\n5,x\n6,7").unwrap_err();
+        let expected =
+            "parse error: in pair > in y > expected a digit at line 3, column 3:\n5,x\n  ^";
+        assert_eq!(expected, error.to_string());
+        let error = pairs.execute(b"1,2;3,4").unwrap_err();
+        let expected = "parse error: expected `\\n` at line 1, column 4:\n1,2;3,4\n   ^";
+        assert_eq!(expected, error.to_string());
+    }
 }
@@ -0,0 +1,137 @@
+use super::*;
+use crate::grid::Grid;
+
+/// Parses a rectangular grid of cells, one line of the input per row, see
+/// `grid_sep_by`.
+pub fn grid<'s, P: Parser<'s>>(cell: P) -> GridParser<P, common::Token<u8>> {
+    grid_sep_by(cell, token(b'\n'))
+}
+
+/// Parses a rectangular grid of cells, with rows separated by `separator`.
+/// Like `sep_by`, the grid ends before a separator that is not followed by
+/// another cell, and the cells of a row end where `cell` fails. Fails when a
+/// row has a different number of cells than the first one.
+pub fn grid_sep_by<'s, P: Parser<'s>, S: Parser<'s>>(cell: P, separator: S) -> GridParser<P, S> {
+    GridParser { cell, separator }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GridParser<P, S> {
+    cell: P,
+    separator: S,
+}
+
+fn width_error(width: usize) -> ParseError {
+    ParseError::custom(format!(
+        "expected a rectangular grid, of {width} cells per row"
+    ))
+}
+
+impl<'s, P: Parser<'s>, S: Parser<'s>> Parser<'s> for GridParser<P, S> {
+    type Output = Grid<P::Output>;
+
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
+        let (first, mut remainder) = self.cell.parse(input)?;
+        let mut cells = vec![first];
+        // Known once the first row ends.
+        let mut width = None;
+        let mut row_len = 1;
+        loop {
+            let failure = loop {
+                match self.cell.parse(remainder) {
+                    Ok(_) if width == Some(row_len) => {
+                        return Err((width_error(row_len), remainder));
+                    }
+                    Ok((cell, after_cell)) => {
+                        cells.push(cell);
+                        remainder = after_cell;
+                        row_len += 1;
+                    }
+                    Err(failure) => break failure,
+                }
+            };
+            let width = *width.get_or_insert(row_len);
+            if row_len != width {
+                // A row cut short by something other than its end is better
+                // explained by what the cell expected.
+                let at_row_end = remainder.is_empty() || self.separator.parse(remainder).is_ok();
+                return Err(if at_row_end {
+                    (width_error(width), remainder)
+                } else {
+                    failure
+                });
+            }
+            error::note_failure(failure);
+
+            let after_sep = match self.separator.parse(remainder) {
+                Ok((_, after_sep)) => after_sep,
+                Err(failure) => {
+                    error::note_failure(failure);
+                    break;
+                }
+            };
+            match self.cell.parse(after_sep) {
+                Ok((cell, after_cell)) => {
+                    cells.push(cell);
+                    remainder = after_cell;
+                    row_len = 1;
+                }
+                Err(failure) => {
+                    error::note_failure(failure);
+                    break;
+                }
+            }
+        }
+        Ok((Grid::from_cells(width.unwrap(), cells), remainder))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        offsets::{Neighbor, Offset},
+        vecs::Vec2,
+    };
+
+    #[test]
+    fn rectangular() {
+        let cells = grid(token((b'.', false)).or(token((b'#', true))));
+        let parsed = cells.execute(b"#..\n.#.\n").unwrap();
+        assert_eq!((3, 2), (parsed.width(), parsed.height()));
+        assert!(parsed[Vec2::new(1, 1)]);
+        assert_eq!(Some(&false), parsed.get(Vec2::new(2, 0)));
+        assert_eq!(None, parsed.get(Vec2::new(3, 0)));
+        assert!(parsed.get_or(Vec2::new(0, 0).neighbor(Offset::X_NEG), true));
+        let neighbors = parsed.neighbors(Vec2::new(0, 0), &Offset::ALL);
+        assert_eq!(3, neighbors.count());
+
+        // The grid stops before the blank line.
+        let (parsed, remainder) = cells.parse(b"#.\n.#\n\nx").unwrap();
+        assert_eq!(
+            (2, 2, &b"\n\nx"[..]),
+            (parsed.width(), parsed.height(), remainder)
+        );
+
+        let patterns = grid_sep_by(pattern!(b'.' | b'#'), token(b'/'));
+        let (parsed, remainder) = patterns.parse(b"#./.. => x").unwrap();
+        assert_eq!(vec![&b"#."[..], b".."], parsed.rows().collect::<Vec<_>>());
+        assert_eq!(b" => x", remainder);
+
+        let error = cells.execute(b"#..\n.#\n...").unwrap_err();
+        assert_eq!(
+            "parse error: expected a rectangular grid, of 3 cells per row at line 2, column 3:\n.#\n  ^",
+            error.to_string()
+        );
+        let error = cells.execute(b"#.\n.#.").unwrap_err();
+        assert_eq!(
+            "parse error: expected a rectangular grid, of 2 cells per row at line 2, column 3:\n.#.\n  ^",
+            error.to_string()
+        );
+        let error = cells.execute(b"#.\n.x").unwrap_err();
+        assert_eq!(
+            "parse error: expected one of `.`, `#` at line 2, column 2:\n.x\n ^",
+            error.to_string()
+        );
+    }
+}
@@ -1,14 +1,25 @@
 mod combi;
 mod common;
 pub mod error;
+mod grid;
 mod multi;
 pub mod numbers;
+mod parse;
+mod precedence;
+mod recursive;
+mod stream;
 
 pub use combi::ParserCombiExt;
-pub use common::{any, digit, pattern, token};
-pub use error::{ParseError, ParseResult};
+pub use common::{any, digit, pattern, phrase, token, ws};
+pub use derive::Parse;
+pub use error::{Expectation, ParseError, ParseResult, Position};
+pub use grid::{grid, grid_sep_by, GridParser};
 pub use multi::{take_while, ParserMultiExt};
-pub use numbers::number;
+pub use numbers::{number, prefixed_number, radix_number};
+pub use parse::Parse;
+pub use precedence::{precedence, Assoc, Precedence};
+pub use recursive::{recursive, Recursive, RecursiveRef};
+pub use stream::{stream, Stream};
 
 pub trait Parser<'s> {
     type Output: 's;
@@ -21,15 +32,27 @@ pub trait Execute<'s, T> {
 
 impl<'s, P: Parser<'s, Output = T>, T> Execute<'s, T> for P {
     fn execute(&self, input: &'s [u8]) -> crate::result::Result<T> {
-        Err(match self.parse(input) {
+        if let Ok((x, [] | [b'\n'])) = error::without_descriptions(|| self.parse(input)) {
+            return Ok(x);
+        }
+
+        // Parse again to find out what was expected, and which failures were
+        // recovered from, as those can explain why parsing stopped early.
+        let (result, recovered) = error::tracking_failures(|| self.parse(input));
+        let (error, remainder) = match result {
             Ok((x, [] | [b'\n'])) => return Ok(x),
-            Ok((_, remainder)) => {
-                ParseError::InputNotConsumed(String::from_utf8_lossy(remainder).into_owned())
-            }
-            Err((e, remainder)) => ParseError::WithRemainder(
-                Box::new(e),
-                String::from_utf8_lossy(remainder).into_owned(),
-            ),
+            Ok((_, remainder)) => (None, remainder),
+            Err((e, remainder)) => (Some(e), remainder),
+        };
+        let recovered = recovered.filter(|(_, len)| match error {
+            Some(_) => *len < remainder.len(),
+            None => *len <= remainder.len(),
+        });
+        let at = |len: usize| Position::new(input, &input[input.len() - len..]);
+        Err(match (recovered, error) {
+            (Some((e, len)), _) => ParseError::WithPosition(Box::new(e), at(len)),
+            (None, Some(e)) => ParseError::WithPosition(Box::new(e), at(remainder.len())),
+            (None, None) => ParseError::InputNotConsumed(at(remainder.len())),
         }
         .into())
     }
@@ -155,14 +155,20 @@ where
         loop {
             let after_sep = match self.separator.parse(remainder) {
                 Ok((_, after_sep)) => after_sep,
-                Err(_) => return Ok((elements, remainder)),
+                Err(failure) => {
+                    error::note_failure(failure);
+                    return Ok((elements, remainder));
+                }
             };
             match self.parser.parse(after_sep) {
                 Ok((element, after_value)) => {
                     remainder = after_value;
                     elements.extend(Some(element));
                 }
-                Err(_) => return Ok((elements, remainder)),
+                Err(failure) => {
+                    error::note_failure(failure);
+                    return Ok((elements, remainder));
+                }
             };
         }
     }
@@ -179,11 +185,18 @@ where
     fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
         let mut accumulator = self.initial.clone();
         let mut remainder = input;
-        while let Ok((value, new_remainder)) = self.parser.parse(remainder) {
-            accumulator = (self.func)(accumulator, value);
-            remainder = new_remainder;
+        loop {
+            match self.parser.parse(remainder) {
+                Ok((value, new_remainder)) => {
+                    accumulator = (self.func)(accumulator, value);
+                    remainder = new_remainder;
+                }
+                Err(failure) => {
+                    error::note_failure(failure);
+                    return Ok((accumulator, remainder));
+                }
+            }
         }
-        Ok((accumulator, remainder))
     }
 }
 
@@ -198,11 +211,18 @@ where
     fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
         let mut accumulator = self.initial.clone();
         let mut remainder = input;
-        while let Ok((value, new_remainder)) = self.parser.parse(remainder) {
-            (self.func)(&mut accumulator, value);
-            remainder = new_remainder;
+        loop {
+            match self.parser.parse(remainder) {
+                Ok((value, new_remainder)) => {
+                    (self.func)(&mut accumulator, value);
+                    remainder = new_remainder;
+                }
+                Err(failure) => {
+                    error::note_failure(failure);
+                    return Ok((accumulator, remainder));
+                }
+            }
         }
-        Ok((accumulator, remainder))
     }
 }
 
@@ -214,11 +234,18 @@ where
 
     fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
         let (mut last_value, mut remainder) = self.parser.parse(input)?;
-        while let Ok((value, new_remainder)) = self.parser.parse(remainder) {
-            last_value = value;
-            remainder = new_remainder;
+        loop {
+            match self.parser.parse(remainder) {
+                Ok((value, new_remainder)) => {
+                    last_value = value;
+                    remainder = new_remainder;
+                }
+                Err(failure) => {
+                    error::note_failure(failure);
+                    return Ok((last_value, remainder));
+                }
+            }
         }
-        Ok((last_value, remainder))
     }
 }
 
@@ -230,11 +257,18 @@ impl<'s, P: Parser<'s>, C: 's + Default + Extend<P::Output>> Parser<'s> for Repe
 
         let (first_value, mut remainder) = self.parser.parse(input)?;
         c.extend(Some(first_value));
-        while let Ok((value, new_remainder)) = self.parser.parse(remainder) {
-            c.extend(Some(value));
-            remainder = new_remainder;
+        loop {
+            match self.parser.parse(remainder) {
+                Ok((value, new_remainder)) => {
+                    c.extend(Some(value));
+                    remainder = new_remainder;
+                }
+                Err(failure) => {
+                    error::note_failure(failure);
+                    return Ok((c, remainder));
+                }
+            }
         }
-        Ok((c, remainder))
     }
 }
 
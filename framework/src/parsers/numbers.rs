@@ -1,36 +1,97 @@
 use std::marker::PhantomData;
 
+#[cfg(feature = "bigint")]
+use num::{bigint::Sign, BigInt, BigUint};
+
 use super::*;
 
+/// Numbers that `number` can parse, in decimal.
+pub trait IsParsableNumber: Sized {
+    fn parse_number(input: &[u8]) -> ParseResult<'_, Self>;
+}
+
+/// Integers, which can also be parsed in other bases, see `radix_number` and
+/// `prefixed_number`.
+pub trait IsParsableInteger: IsParsableNumber {
+    fn parse_radix(input: &[u8], radix: u32) -> ParseResult<'_, Self>;
+    fn parse_prefixed(input: &[u8]) -> ParseResult<'_, Self>;
+}
+
+#[inline(always)]
+fn digit_value(c: u8, radix: u32) -> Option<u8> {
+    let value = match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'z' => c - b'a' + 10,
+        b'A'..=b'Z' => c - b'A' + 10,
+        _ => return None,
+    };
+    ((value as u32) < radix).then_some(value)
+}
+
+/// Determines the base from a `0x`, `0o` or `0b` prefix, and strips it.
+fn prefixed_radix(input: &[u8]) -> (u32, &[u8]) {
+    match input {
+        [b'0', b'x' | b'X', rest @ ..] => (16, rest),
+        [b'0', b'o' | b'O', rest @ ..] => (8, rest),
+        [b'0', b'b' | b'B', rest @ ..] => (2, rest),
+        _ => (10, input),
+    }
+}
+
+/// Parses an optional + or - at the start to indicate a sign, followed by the
+/// magnitude, which `convert` turns into a signed number if it is in range.
+#[inline(always)]
+fn parse_signed<'s, U, S>(
+    input: &'s [u8],
+    magnitude: impl FnOnce(&'s [u8]) -> ParseResult<'s, U>,
+    convert: impl FnOnce(U, bool) -> Option<S>,
+) -> ParseResult<'s, S> {
+    let (is_negative, remainder) = match input.first() {
+        Some(&b'-') => (true, &input[1..]),
+        Some(&b'+') => (false, &input[1..]),
+        _ => (false, input),
+    };
+    let (number, remainder) = magnitude(remainder)?;
+    match convert(number, is_negative) {
+        Some(number) => Ok((number, remainder)),
+        None => Err((ParseError::Overflow, input)),
+    }
+}
+
 macro_rules! impl_uint_parsing {
     ($kind:tt) => {
-        impl $crate::parsers::numbers::IsParsableNumber for $kind {}
-        impl<'s> $crate::parsers::Parser<'s> for NumberParser<$kind> {
-            type Output = $kind;
+        impl IsParsableNumber for $kind {
+            #[inline]
+            fn parse_number(input: &[u8]) -> ParseResult<'_, Self> {
+                Self::parse_radix(input, 10)
+            }
+        }
 
-            fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
+        impl IsParsableInteger for $kind {
+            #[inline]
+            fn parse_radix(input: &[u8], radix: u32) -> ParseResult<'_, Self> {
                 let first_char = *input.first().ok_or((ParseError::EmptyInput, input))?;
-                if !matches!(first_char, b'0'..=b'9') {
-                    return Err((ParseError::ExpectedDigit, input));
-                }
+                let first_digit =
+                    digit_value(first_char, radix).ok_or((ParseError::ExpectedDigit, input))?;
 
                 let mut remainder = &input[1..];
 
-                let mut x = (first_char as $kind) - (b'0' as $kind);
-                loop {
-                    let next_digit = match remainder.first() {
-                        Some(&c @ b'0'..=b'9') => (c as $kind) - (b'0' as $kind),
-                        _ => break,
-                    };
+                let mut x = first_digit as $kind;
+                while let Some(digit) = remainder.first().and_then(|&c| digit_value(c, radix)) {
                     x = x
-                        .checked_mul(10)
-                        .and_then(|x| x.checked_add(next_digit))
+                        .checked_mul(radix as $kind)
+                        .and_then(|x| x.checked_add(digit as $kind))
                         .ok_or((ParseError::Overflow, input))?;
                     remainder = &remainder[1..];
                 }
 
                 Ok((x, remainder))
             }
+
+            fn parse_prefixed(input: &[u8]) -> ParseResult<'_, Self> {
+                let (radix, digits) = prefixed_radix(input);
+                Self::parse_radix(digits, radix)
+            }
         }
     };
 }
@@ -39,27 +100,82 @@ macro_rules! impl_sint_parsing {
     ($kind:tt, $unsigned:tt) => {
         /// Parses an integer. Allows an optional + or - at the start to
         /// indicate a sign.
-        impl $crate::parsers::numbers::IsParsableNumber for $kind {}
-        impl<'s> $crate::parsers::Parser<'s> for NumberParser<$kind> {
-            type Output = $kind;
-
-            fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
-                let (is_negative, remainder) = match input.first() {
-                    Some(&b'-') => (true, &input[1..]),
-                    Some(&b'+') => (false, &input[1..]),
-                    _ => (false, input),
-                };
-                let (number, remainder) =
-                    $crate::parsers::number::<$unsigned>().parse(remainder)?;
-                const MAX: $unsigned = $kind::MAX as $unsigned;
-                const MAX_PLUS_ONE: $unsigned = MAX + 1;
-                let number = match (number, is_negative) {
-                    (0..=MAX, false) => number as $kind,
-                    (0..=MAX, true) => -(number as $kind),
-                    (MAX_PLUS_ONE, true) => $kind::MIN,
-                    _ => return Err((ParseError::Overflow, input)),
+        impl IsParsableNumber for $kind {
+            #[inline]
+            fn parse_number(input: &[u8]) -> ParseResult<'_, Self> {
+                Self::parse_radix(input, 10)
+            }
+        }
+
+        impl IsParsableInteger for $kind {
+            #[inline]
+            fn parse_radix(input: &[u8], radix: u32) -> ParseResult<'_, Self> {
+                parse_signed(input, |r| $unsigned::parse_radix(r, radix), to_signed)
+            }
+
+            fn parse_prefixed(input: &[u8]) -> ParseResult<'_, Self> {
+                parse_signed(input, $unsigned::parse_prefixed, to_signed)
+            }
+        }
+
+        impl ToSigned<$kind> for $unsigned {
+            #[inline(always)]
+            fn to_signed(self, is_negative: bool) -> Option<$kind> {
+                if is_negative {
+                    // The magnitude of the minimum wraps to itself.
+                    (self <= $kind::MIN.unsigned_abs()).then(|| (self as $kind).wrapping_neg())
+                } else {
+                    $kind::try_from(self).ok()
+                }
+            }
+        }
+    };
+}
+
+trait ToSigned<S> {
+    fn to_signed(self, is_negative: bool) -> Option<S>;
+}
+
+#[inline(always)]
+fn to_signed<U: ToSigned<S>, S>(magnitude: U, is_negative: bool) -> Option<S> {
+    magnitude.to_signed(is_negative)
+}
+
+macro_rules! impl_float_parsing {
+    ($kind:tt) => {
+        /// Parses a decimal number, with an optional sign, fraction, and
+        /// exponent, such as `-1.5e3`. A number too large for the type is an
+        /// overflow, rather than infinity.
+        impl IsParsableNumber for $kind {
+            fn parse_number(input: &[u8]) -> ParseResult<'_, Self> {
+                let digits = |from: usize| {
+                    let rest = input.get(from..).unwrap_or_default();
+                    rest.iter().take_while(|c| c.is_ascii_digit()).count()
                 };
-                Ok((number, remainder))
+                let mut len = matches!(input.first(), Some(b'+' | b'-')) as usize;
+                match digits(len) {
+                    0 if len == input.len() => return Err((ParseError::EmptyInput, &input[len..])),
+                    0 => return Err((ParseError::ExpectedDigit, &input[len..])),
+                    integer => len += integer,
+                }
+                if input.get(len) == Some(&b'.') && digits(len + 1) > 0 {
+                    len += 1 + digits(len + 1);
+                }
+                if matches!(input.get(len), Some(b'e' | b'E')) {
+                    let sign = matches!(input.get(len + 1), Some(b'+' | b'-')) as usize;
+                    let exponent = digits(len + 1 + sign);
+                    if exponent > 0 {
+                        len += 1 + sign + exponent;
+                    }
+                }
+
+                // Only ASCII digits and signs were matched, which is valid
+                // UTF-8, and valid syntax for a float.
+                let text = std::str::from_utf8(&input[..len]).unwrap();
+                match text.parse::<$kind>() {
+                    Ok(x) if x.is_finite() => Ok((x, &input[len..])),
+                    _ => Err((ParseError::Overflow, input)),
+                }
             }
         }
     };
@@ -79,10 +195,108 @@ impl_sint_parsing!(i64, u64);
 impl_sint_parsing!(i128, u128);
 impl_sint_parsing!(isize, usize);
 
-pub trait IsParsableNumber {}
+impl_float_parsing!(f32);
+impl_float_parsing!(f64);
+
+#[cfg(feature = "bigint")]
+impl IsParsableNumber for BigUint {
+    fn parse_number(input: &[u8]) -> ParseResult<'_, Self> {
+        Self::parse_radix(input, 10)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl IsParsableInteger for BigUint {
+    fn parse_radix(input: &[u8], radix: u32) -> ParseResult<'_, Self> {
+        let len = input
+            .iter()
+            .take_while(|&&c| digit_value(c, radix).is_some())
+            .count();
+        match BigUint::parse_bytes(&input[..len], radix) {
+            Some(x) => Ok((x, &input[len..])),
+            None if input.is_empty() => Err((ParseError::EmptyInput, input)),
+            None => Err((ParseError::ExpectedDigit, input)),
+        }
+    }
+
+    fn parse_prefixed(input: &[u8]) -> ParseResult<'_, Self> {
+        let (radix, digits) = prefixed_radix(input);
+        Self::parse_radix(digits, radix)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl ToSigned<BigInt> for BigUint {
+    fn to_signed(self, is_negative: bool) -> Option<BigInt> {
+        let sign = if is_negative { Sign::Minus } else { Sign::Plus };
+        Some(BigInt::from_biguint(sign, self))
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl IsParsableNumber for BigInt {
+    fn parse_number(input: &[u8]) -> ParseResult<'_, Self> {
+        Self::parse_radix(input, 10)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl IsParsableInteger for BigInt {
+    fn parse_radix(input: &[u8], radix: u32) -> ParseResult<'_, Self> {
+        parse_signed(input, |r| BigUint::parse_radix(r, radix), to_signed)
+    }
+
+    fn parse_prefixed(input: &[u8]) -> ParseResult<'_, Self> {
+        parse_signed(input, BigUint::parse_prefixed, to_signed)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct NumberParser<T: IsParsableNumber>(PhantomData<T>);
+impl<'s, T: IsParsableNumber + 's> Parser<'s> for NumberParser<T> {
+    type Output = T;
+
+    #[inline]
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, T> {
+        T::parse_number(input)
+    }
+}
 
+/// Parses a decimal number.
 pub const fn number<T: IsParsableNumber>() -> NumberParser<T> {
     NumberParser(PhantomData)
 }
+
+#[derive(Debug, Clone, Copy)]
+pub struct RadixParser<T: IsParsableInteger>(u32, PhantomData<T>);
+impl<'s, T: IsParsableInteger + 's> Parser<'s> for RadixParser<T> {
+    type Output = T;
+
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, T> {
+        T::parse_radix(input, self.0)
+    }
+}
+
+/// Parses an integer in the given base, which is between 2 and 36. Digits
+/// past 9 are letters, in either case.
+pub const fn radix_number<T: IsParsableInteger>(radix: u32) -> RadixParser<T> {
+    assert!(2 <= radix && radix <= 36, "radix must be between 2 and 36");
+    RadixParser(radix, PhantomData)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PrefixedParser<T: IsParsableInteger>(PhantomData<T>);
+impl<'s, T: IsParsableInteger + 's> Parser<'s> for PrefixedParser<T> {
+    type Output = T;
+
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, T> {
+        T::parse_prefixed(input)
+    }
+}
+
+/// Parses an integer that is hexadecimal when prefixed by `0x`, octal when
+/// prefixed by `0o`, binary when prefixed by `0b`, and decimal otherwise. The
+/// sign of a signed integer goes before the prefix, as in `-0x1f`.
+pub const fn prefixed_number<T: IsParsableInteger>() -> PrefixedParser<T> {
+    PrefixedParser(PhantomData)
+}
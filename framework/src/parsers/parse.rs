@@ -0,0 +1,100 @@
+use super::*;
+
+/// Types with a parser of their own, usually implemented with
+/// `#[derive(Parse)]`, which builds it out of the `Parse` implementations of
+/// its fields.
+pub trait Parse: Sized {
+    fn parser<'s>() -> impl Parser<'s, Output = Self>
+    where
+        Self: 's;
+}
+
+macro_rules! impl_number_parse {
+    ($($kind:ty),*) => {$(
+        impl Parse for $kind {
+            fn parser<'s>() -> impl Parser<'s, Output = Self> {
+                number::<$kind>()
+            }
+        }
+    )*};
+}
+
+impl_number_parse!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+#[cfg(feature = "bigint")]
+impl_number_parse!(num::BigInt, num::BigUint);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq, Parse)]
+    enum Op {
+        #[parse(token = "inc")]
+        Inc,
+        #[parse(token = "dec")]
+        Dec,
+        Nop,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Parse)]
+    #[parse(sep = " ", context = "instruction")]
+    struct Instruction {
+        op: Op,
+        #[parse(prefix = "#", context = "register")]
+        register: u8,
+        #[parse(with = "number::<i32>().opt().map(Option::unwrap_or_default)")]
+        amount: i32,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Parse)]
+    #[parse(prefix = "< ", sep = ", ", suffix = " >")]
+    struct Pair(u32, u32);
+
+    // `a` is a prefix of `ab`, which is still tried first.
+    #[derive(Debug, PartialEq, Eq, Parse)]
+    #[parse(crate = "crate")]
+    enum Prefixed {
+        A,
+        Ab,
+    }
+
+    #[test]
+    fn derived() {
+        let instructions = Instruction::parser().sep_by::<_, Vec<_>>(token(b'\n'));
+        let expected = vec![
+            Instruction {
+                op: Op::Inc,
+                register: 1,
+                amount: 5,
+            },
+            Instruction {
+                op: Op::Nop,
+                register: 2,
+                amount: 0,
+            },
+        ];
+        assert_eq!(
+            Ok(expected),
+            instructions
+                .execute(b"inc #1 5\nNop  #2")
+                .map_err(|e| e.to_string())
+        );
+        let error = instructions.execute(b"inc #1 5\ndec 2 3").unwrap_err();
+        let expected = "parse error: in instruction > in register > expected `#` \
+                        at line 2, column 5:\ndec 2 3\n    ^";
+        assert_eq!(expected, error.to_string());
+
+        assert_eq!(
+            Ok(Pair(3, 4)),
+            Pair::parser()
+                .execute(b"< 3, 4 >")
+                .map_err(|e| e.to_string())
+        );
+
+        let prefixed = Prefixed::parser().sep_by::<_, Vec<_>>(token(b' '));
+        assert_eq!(
+            Ok(vec![Prefixed::Ab, Prefixed::A]),
+            prefixed.execute(b"Ab A").map_err(|e| e.to_string())
+        );
+    }
+}
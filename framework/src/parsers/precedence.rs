@@ -0,0 +1,144 @@
+use std::marker::PhantomData;
+
+use super::*;
+
+/// How a chain of operators with the same precedence groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`.
+    Right,
+}
+
+/// Parses operands separated by infix operators, and combines them with
+/// `apply`, respecting the precedence and associativity of the operators.
+/// The `operator` parser outputs the operator along with its precedence,
+/// where higher binds tighter, and associativity, such as
+/// `token((b'*', (Op::Mul, 2, Assoc::Left)))`.
+///
+/// Use `recursive` for operands that contain expressions themselves, such as
+/// parenthesized expressions.
+pub fn precedence<'s, P, O, Op, F>(operand: P, operator: O, apply: F) -> Precedence<P, O, Op, F>
+where
+    P: Parser<'s>,
+    O: Parser<'s, Output = (Op, u8, Assoc)>,
+    F: Fn(P::Output, Op, P::Output) -> P::Output,
+{
+    Precedence {
+        operand,
+        operator,
+        apply,
+        _operator: PhantomData,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Precedence<P, O, Op, F> {
+    operand: P,
+    operator: O,
+    apply: F,
+    _operator: PhantomData<fn() -> Op>,
+}
+
+impl<'s, P, O, Op, F> Precedence<P, O, Op, F>
+where
+    P: Parser<'s>,
+    O: Parser<'s, Output = (Op, u8, Assoc)>,
+    F: Fn(P::Output, Op, P::Output) -> P::Output,
+{
+    /// Parses an expression of only the operators that have at least the
+    /// given precedence.
+    fn climb(&self, input: &'s [u8], min_precedence: u8) -> ParseResult<'s, P::Output> {
+        let (mut lhs, mut remainder) = self.operand.parse(input)?;
+        loop {
+            let (op, after_op, next_precedence) = match self.operator.parse(remainder) {
+                Ok(((op, precedence, assoc), after_op)) if precedence >= min_precedence => {
+                    let next_precedence = match assoc {
+                        Assoc::Left => precedence.checked_add(1),
+                        Assoc::Right => Some(precedence),
+                    };
+                    (op, after_op, next_precedence)
+                }
+                // Belongs to an enclosing expression.
+                Ok(_) => return Ok((lhs, remainder)),
+                Err(failure) => {
+                    error::note_failure(failure);
+                    return Ok((lhs, remainder));
+                }
+            };
+            let (rhs, after_rhs) = match next_precedence {
+                Some(next_precedence) => self.climb(after_op, next_precedence)?,
+                // No operator binds tighter than the highest precedence.
+                None => self.operand.parse(after_op)?,
+            };
+            lhs = (self.apply)(lhs, op, rhs);
+            remainder = after_rhs;
+        }
+    }
+}
+
+impl<'s, P, O, Op, F> Parser<'s> for Precedence<P, O, Op, F>
+where
+    P: Parser<'s>,
+    O: Parser<'s, Output = (Op, u8, Assoc)>,
+    F: Fn(P::Output, Op, P::Output) -> P::Output,
+{
+    type Output = P::Output;
+
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, Self::Output> {
+        self.climb(input, 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let expression = recursive(|expression| {
+            let parenthesized = token(b'(').then(expression).trailed(token(b')'));
+            let operand = number::<i64>().or(parenthesized);
+            #[rustfmt::skip]
+            let operator = token((b'+', (b'+', 1, Assoc::Left)))
+                       .or(token((b'-', (b'-', 1, Assoc::Left))))
+                       .or(token((b'*', (b'*', 2, Assoc::Left))))
+                       .or(token((b'^', (b'^', 3, Assoc::Right))));
+            precedence(operand, operator, |a, op, b| match op {
+                b'+' => a + b,
+                b'-' => a - b,
+                b'*' => a * b,
+                _ => a.pow(b as u32),
+            })
+        });
+
+        #[rustfmt::skip]
+        let cases: [(&[u8], i64); 7] = [
+            (b"1",               1),
+            (b"1+2*3",           7),
+            (b"(1+2)*3",         9),
+            (b"10-4-3",          3),
+            (b"2^3^2",         512),
+            (b"2*3^2-1",        17),
+            (b"2*(3-(4-5))*2",  16),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                Ok(expected),
+                expression.execute(input).map_err(|e| e.to_string())
+            );
+        }
+
+        let error = expression.execute(b"1+(2*)").unwrap_err();
+        assert_eq!(
+            "parse error: expected one of a digit, `(` at line 1, column 6:\n1+(2*)\n     ^",
+            error.to_string()
+        );
+
+        // Left associativity at the highest precedence.
+        let operator = token((b'-', (b'-', u8::MAX, Assoc::Left)));
+        let expression = precedence(number::<i64>(), operator, |a, _, b| a - b);
+        assert_eq!(3, expression.execute(b"10-4-3").unwrap());
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    cell::OnceCell,
+    fmt,
+    rc::{Rc, Weak},
+};
+
+use super::*;
+
+type Cell<'s, T> = OnceCell<Box<dyn Parser<'s, Output = T> + 's>>;
+
+/// A parser for a recursive grammar, built by `recursive`.
+pub struct Recursive<'s, T>(Rc<Cell<'s, T>>);
+
+/// A reference to the parser that `recursive` is building, to be used within
+/// its own definition. It only parses once the definition is complete.
+pub struct RecursiveRef<'s, T>(Weak<Cell<'s, T>>);
+
+/// Builds a parser that refers to itself. The definition receives a reference
+/// to the parser being defined, so that a list of lists can be defined as
+/// `recursive(|list| token(b'[').then(list.sep_by(token(b','))).trailed(token(b']')))`.
+pub fn recursive<'s, T, P, F>(definition: F) -> Recursive<'s, T>
+where
+    T: 's,
+    P: Parser<'s, Output = T> + 's,
+    F: FnOnce(RecursiveRef<'s, T>) -> P,
+{
+    // The definition only holds a weak reference, so that it doesn't keep
+    // itself alive.
+    let cell = Rc::new(OnceCell::new());
+    let parser = definition(RecursiveRef(Rc::downgrade(&cell)));
+    _ = cell.set(Box::new(parser) as Box<dyn Parser<'s, Output = T> + 's>);
+    Recursive(cell)
+}
+
+impl<'s, T: 's> Parser<'s> for Recursive<'s, T> {
+    type Output = T;
+
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, T> {
+        self.0
+            .get()
+            .expect("recursive parser is defined")
+            .parse(input)
+    }
+}
+
+impl<'s, T: 's> Parser<'s> for RecursiveRef<'s, T> {
+    type Output = T;
+
+    fn parse(&self, input: &'s [u8]) -> ParseResult<'s, T> {
+        let cell = self
+            .0
+            .upgrade()
+            .expect("recursive parser is used while it is alive");
+        let parser = cell
+            .get()
+            .expect("recursive parser is used after its definition");
+        parser.parse(input)
+    }
+}
+
+impl<T> Clone for Recursive<'_, T> {
+    fn clone(&self) -> Self {
+        Recursive(self.0.clone())
+    }
+}
+
+impl<T> Clone for RecursiveRef<'_, T> {
+    fn clone(&self) -> Self {
+        RecursiveRef(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for Recursive<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Recursive")
+    }
+}
+
+impl<T> fmt::Debug for RecursiveRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RecursiveRef")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Tree {
+        Leaf(u32),
+        Node(Vec<Tree>),
+    }
+
+    #[test]
+    fn nested() {
+        let tree = recursive(|tree| {
+            let node = token(b'[')
+                .then(tree.sep_by(token(b',')).opt())
+                .trailed(token(b']'))
+                .map(|children| Tree::Node(children.unwrap_or_default()));
+            number::<u32>().map(Tree::Leaf).or(node)
+        });
+        use Tree::*;
+        assert_eq!(
+            Ok(Node(vec![
+                Leaf(1),
+                Node(vec![]),
+                Node(vec![Leaf(2), Node(vec![Leaf(3)])])
+            ])),
+            tree.execute(b"[1,[],[2,[3]]]").map_err(|e| e.to_string())
+        );
+        let error = tree.execute(b"[1,[2,]]").unwrap_err();
+        assert_eq!(
+            "parse error: expected one of a digit, `[` at line 1, column 7:\n[1,[2,]]\n      ^",
+            error.to_string()
+        );
+    }
+}
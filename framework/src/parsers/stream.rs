@@ -0,0 +1,263 @@
+use std::io::{ErrorKind, Read};
+
+use super::*;
+use crate::{error::Error, result::Result};
+
+/// Number of bytes requested from the reader at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// How much of the current line is kept around for reporting errors, longer
+/// lines are discarded once parsed, such that they don't fill up the buffer.
+const MAX_KEPT_LINE: usize = 4 * 1024;
+
+/// How much input must follow the point where the parser stopped, or failed,
+/// before the outcome is trusted, short of the end of the input. Parsers fail
+/// at the start of a token, even if only its end is missing, so a token split
+/// across reads needs to be read in full before a failure is reported.
+const LOOKAHEAD: usize = 1024;
+
+/// Parses a list of items separated by `separator` from a reader, like
+/// `sep_by`, but yields the items one at a time, reading only as much of the
+/// input as is needed for the next item. An item is complete once the parser
+/// stops at least `LOOKAHEAD` bytes before the end of the input read so far,
+/// or when all of the input has been read.
+///
+/// As parsed input is discarded, items cannot borrow from the input.
+pub fn stream<R, P, S, T>(reader: R, parser: P, separator: S) -> Stream<R, P, S>
+where
+    R: Read,
+    P: for<'s> Parser<'s, Output = T>,
+    S: for<'s> Parser<'s>,
+{
+    Stream {
+        reader,
+        parser,
+        separator,
+        buffer: Vec::new(),
+        start: 0,
+        at_end: false,
+        first: true,
+        done: false,
+        discarded: 0,
+        discarded_lines: 0,
+        discarded_columns: 0,
+    }
+}
+
+#[derive(Debug)]
+pub struct Stream<R, P, S> {
+    reader: R,
+    parser: P,
+    separator: S,
+    buffer: Vec<u8>,
+    /// Start of the input that has not been parsed yet.
+    start: usize,
+    at_end: bool,
+    first: bool,
+    done: bool,
+    /// Number of bytes, lines, and characters on the current line, that were
+    /// removed from the front of the buffer.
+    discarded: usize,
+    discarded_lines: usize,
+    discarded_columns: usize,
+}
+
+impl<R, P, S, T> Stream<R, P, S>
+where
+    R: Read,
+    P: for<'s> Parser<'s, Output = T>,
+    S: for<'s> Parser<'s>,
+{
+    fn parse_next<'s>(&self, input: &'s [u8]) -> ParseResult<'s, T> {
+        if self.first {
+            self.parser.parse(input)
+        } else {
+            let (_, remainder) = self.separator.parse(input)?;
+            self.parser.parse(remainder)
+        }
+    }
+
+    /// Reads another chunk into the buffer, after discarding parsed input.
+    fn fill(&mut self) -> Result<()> {
+        let parsed = &self.buffer[..self.start];
+        let line_start = parsed.iter().rposition(|&c| c == b'\n').map(|i| i + 1);
+        let keep_from = match line_start {
+            Some(line_start) if self.start - line_start <= MAX_KEPT_LINE => line_start,
+            _ if self.discarded_columns == 0 && self.start <= MAX_KEPT_LINE => 0,
+            _ => self.start,
+        };
+        let discarded = &self.buffer[..keep_from];
+        let columns = |bytes: &[u8]| String::from_utf8_lossy(bytes).chars().count();
+        match discarded.iter().rposition(|&c| c == b'\n') {
+            Some(i) => {
+                self.discarded_lines += discarded.iter().filter(|&&c| c == b'\n').count();
+                self.discarded_columns = columns(&discarded[i + 1..]);
+            }
+            None => self.discarded_columns += columns(discarded),
+        }
+        self.discarded += keep_from;
+        self.buffer.drain(..keep_from);
+        self.start -= keep_from;
+
+        let len = self.buffer.len();
+        self.buffer.resize(len + CHUNK_SIZE, 0);
+        let read = loop {
+            match self.reader.read(&mut self.buffer[len..]) {
+                Ok(read) => break read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.buffer.truncate(len);
+                    return Err(e.into());
+                }
+            }
+        };
+        self.buffer.truncate(len + read);
+        self.at_end = read == 0;
+        Ok(())
+    }
+
+    /// Locates the failure to parse the next item in the stream.
+    fn error(&self, (error, remainder): (ParseError, &[u8])) -> Error {
+        let mut position = Position::new(&self.buffer, remainder);
+        position.offset += self.discarded;
+        if position.line == 1 && self.discarded_columns > 0 {
+            // The start of the line is gone, so it can't be shown.
+            position.column += self.discarded_columns;
+            position.snippet = None;
+        }
+        position.line += self.discarded_lines;
+        ParseError::WithPosition(Box::new(error), position).into()
+    }
+
+    fn next_item(&mut self) -> Result<Option<T>> {
+        loop {
+            let input = &self.buffer[self.start..];
+            // A line ending may be all that is left.
+            if matches!(input, [] | [b'\n']) {
+                if !self.at_end {
+                    self.fill()?;
+                    continue;
+                }
+                if !self.first || input.is_empty() {
+                    return Ok(None);
+                }
+            }
+            // Stopping close to the end of what was read so far, the parser
+            // may get further with more input.
+            let settled = |remainder: &[u8]| self.at_end || remainder.len() >= LOOKAHEAD;
+            match error::without_descriptions(|| self.parse_next(input)) {
+                Ok((item, remainder)) if settled(remainder) => {
+                    self.start = self.buffer.len() - remainder.len();
+                    self.first = false;
+                    return Ok(Some(item));
+                }
+                Err((_, remainder)) if settled(remainder) => {
+                    // Read the rest of the line, to show it in the error.
+                    while !self.at_end
+                        && !self.buffer[self.start..].contains(&b'\n')
+                        && self.buffer.len() - self.start <= MAX_KEPT_LINE
+                    {
+                        self.fill()?;
+                    }
+                    // Parse again to describe the failure, which the input
+                    // that was read since may have resolved.
+                    let input = &self.buffer[self.start..];
+                    match error::tracking_failures(|| self.parse_next(input)).0 {
+                        Ok((item, remainder)) => {
+                            self.start = self.buffer.len() - remainder.len();
+                            self.first = false;
+                            return Ok(Some(item));
+                        }
+                        Err(failure) => return Err(self.error(failure)),
+                    }
+                }
+                _ => self.fill()?,
+            }
+        }
+    }
+}
+
+impl<R, P, S, T> Iterator for Stream<R, P, S>
+where
+    R: Read,
+    P: for<'s> Parser<'s, Output = T>,
+    S: for<'s> Parser<'s>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.next_item().transpose();
+        self.done = !matches!(item, Some(Ok(_)));
+        item
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Hands out the input a few bytes at a time.
+    struct Trickle<'a>(&'a [u8], usize);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.1.min(buf.len()).min(self.0.len());
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    fn collect(input: &[u8], chunk: usize) -> std::result::Result<Vec<u32>, String> {
+        stream(Trickle(input, chunk), number::<u32>(), token(b','))
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn items() {
+        for chunk in [1, 2, 3, 100] {
+            assert_eq!(Ok(vec![12, 345, 6]), collect(b"12,345,6\n", chunk));
+            assert_eq!(Ok(vec![7]), collect(b"7", chunk));
+            assert_eq!(Ok(vec![]), collect(b"", chunk));
+        }
+
+        let expected = "parse error: expected a digit at line 1, column 7:\n12,34,x\n      ^";
+        assert_eq!(Err(expected.to_owned()), collect(b"12,34,x", 2));
+        let expected = "parse error: expected `,` at line 1, column 3:\n12;3\n  ^";
+        assert_eq!(Err(expected.to_owned()), collect(b"12;3", 1));
+
+        // Tokens split across reads are read in full.
+        let items = stream(Trickle(b"1, 2", 1), number::<u32>(), token(b", "));
+        let items = items.collect::<Result<Vec<_>>>().map_err(|e| e.to_string());
+        assert_eq!(Ok(vec![1, 2]), items);
+
+        // Lines that were parsed before the failure still count.
+        let lines = stream(Trickle(b"1\n2\n3\n4x\n", 3), number::<u32>(), token(b'\n'));
+        let error = lines.collect::<Result<Vec<_>>>().unwrap_err();
+        let expected = "parse error: expected `\\n` at line 4, column 2:\n4x\n ^";
+        assert_eq!(expected, error.to_string());
+
+        // Long lines are not kept in memory, so only the column is known.
+        let long = [b"1,".repeat(50_000), b"x".to_vec()].concat();
+        let expected = "parse error: expected a digit at line 1, column 100001";
+        assert_eq!(Err(expected.to_owned()), collect(&long, 4096));
+
+        // The first items are yielded before the rest of the input is read.
+        let mut items = stream(
+            Trickle(b"1,2,", 1).chain(std::io::repeat(b'x')),
+            number::<u32>(),
+            token(b','),
+        );
+        assert_eq!(
+            (Some(1), Some(2)),
+            (
+                items.next().map(|r| r.unwrap()),
+                items.next().map(|r| r.unwrap())
+            )
+        );
+    }
+}
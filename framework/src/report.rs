@@ -0,0 +1,457 @@
+use crate::{
+    answers::{Answers, Verification},
+    error::Error,
+    options::{Format, Options},
+    outputs::ColoredOutput,
+    result::Result,
+};
+use colored::Colorize;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const OUTPUT_WIDTH: usize = 40;
+
+/// Statistics of a report, used to combine the reports of days that ran in
+/// parallel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub mismatches: usize,
+}
+
+/// Writes the results of each day and part, in the requested format.
+pub struct Report<'o> {
+    year: u32,
+    format: Format,
+    out: &'o mut dyn Write,
+    /// Directory of the recorded answers, `None` when the inputs are not the
+    /// cached puzzle inputs, so the recorded answers do not apply.
+    answers_dir: Option<PathBuf>,
+    answers: Option<Answers>,
+    record: bool,
+    check: bool,
+    mismatches: usize,
+    day: u32,
+    part: &'static str,
+    day_elapsed: Duration,
+    total_elapsed: Duration,
+}
+
+impl<'o> Report<'o> {
+    /// The recorded answers only apply to the puzzle inputs, see
+    /// `Inputs::are_puzzle_inputs`.
+    pub fn new(
+        year: u32,
+        options: &Options,
+        puzzle_inputs: bool,
+        out: &'o mut dyn Write,
+    ) -> Report<'o> {
+        Report {
+            year,
+            format: options.format,
+            out,
+            answers_dir: puzzle_inputs.then(|| Path::new("./answers").join(year.to_string())),
+            answers: None,
+            record: options.record,
+            check: options.check,
+            mismatches: 0,
+            day: 0,
+            part: "",
+            day_elapsed: Duration::ZERO,
+            total_elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Overrides the directory containing the recorded answers.
+    pub fn with_answers_dir(mut self, answers_dir: impl Into<PathBuf>) -> Self {
+        self.answers_dir = Some(answers_dir.into());
+        self
+    }
+
+    pub fn summary(&self) -> Summary {
+        Summary {
+            mismatches: self.mismatches,
+        }
+    }
+
+    /// Writes the output of another report, and includes its statistics.
+    /// Its time is not included, see `add_elapsed`.
+    pub fn append(&mut self, output: &[u8], summary: Summary) -> Result<()> {
+        self.out.write_all(output)?;
+        _ = self.out.flush();
+        self.mismatches += summary.mismatches;
+        Ok(())
+    }
+
+    /// Adds to the total time. Days that ran in parallel overlap, so rather
+    /// than the sum of their times, the time it took to run them all is added.
+    pub fn add_elapsed(&mut self, elapsed: Duration) {
+        self.total_elapsed += elapsed;
+    }
+
+    pub fn begin(&mut self) -> Result<()> {
+        match self.format {
+            Format::Pretty => writeln!(
+                self.out,
+                "\n🎄 {} {} {} {} 🎄\n",
+                "Advent".bright_red().bold(),
+                "of".bright_green(),
+                "Code".blue().bold(),
+                self.year.to_string().bright_magenta().bold()
+            )?,
+            Format::Json => {}
+            Format::Csv => writeln!(self.out, "day,part,answer,error,verified,elapsed_ns")?,
+        }
+        Ok(())
+    }
+
+    /// Finishes the report. In check mode, fails if any answer did not match
+    /// its recorded answer, which includes parts that failed, or whose day
+    /// failed before they ran.
+    pub fn end(&mut self) -> Result<()> {
+        if self.format == Format::Pretty {
+            writeln!(
+                self.out,
+                "\n{} {}\n",
+                "Total".bright_blue(),
+                format_duration(self.total_elapsed).bright_green()
+            )?;
+        }
+        if self.check && self.mismatches > 0 {
+            return Err(Error::AnswerMismatch(self.mismatches));
+        }
+        Ok(())
+    }
+
+    pub fn begin_day(&mut self, day: u32) -> Result<()> {
+        self.day_elapsed = Duration::ZERO;
+        self.load_answers(day)?;
+        if self.format == Format::Pretty {
+            write!(
+                self.out,
+                "{} {}",
+                "Day".bright_blue(),
+                format!("{:>2}", day).bright_red().bold()
+            )?;
+        }
+        Ok(())
+    }
+
+    fn load_answers(&mut self, day: u32) -> Result<()> {
+        self.day = day;
+        self.answers = match &self.answers_dir {
+            Some(answers_dir) => Some(Answers::load(answers_dir, day)?),
+            None => None,
+        };
+        Ok(())
+    }
+
+    pub fn end_day(&mut self) -> Result<()> {
+        self.total_elapsed += self.day_elapsed;
+        if let Some(answers) = &mut self.answers {
+            answers.save()?;
+        }
+        if self.format == Format::Pretty {
+            writeln!(
+                self.out,
+                " {} {}",
+                "=".magenta(),
+                format_duration(self.day_elapsed).bright_green()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Records how long it took to parse the input of the current day.
+    pub fn end_parse(&mut self, elapsed: Duration) -> Result<()> {
+        self.day_elapsed += elapsed;
+        if self.format == Format::Pretty {
+            write!(self.out, " {}", format_duration(elapsed).dimmed())?;
+        }
+        Ok(())
+    }
+
+    pub fn begin_part(&mut self, part: &'static str) -> Result<()> {
+        self.part = part;
+        if self.format == Format::Pretty {
+            write!(self.out, " {} {} ", "::".magenta(), part.bright_yellow())?;
+            _ = self.out.flush();
+        }
+        Ok(())
+    }
+
+    /// Records the outcome of the current part. In the pretty format, errors
+    /// are returned, in the structured formats they become part of the record.
+    pub fn end_part(&mut self, result: Result<ColoredOutput>, elapsed: Duration) -> Result<()> {
+        self.day_elapsed += elapsed;
+        let (answer, error) = split_result(&result);
+        let verification = self.verify(answer);
+        match self.format {
+            Format::Pretty => {
+                let result = result?;
+                let remaining_space = OUTPUT_WIDTH.saturating_sub(self.part.len() + 1);
+                let str_len = result.value().len() - result.control_count();
+                let remaining_space = remaining_space.saturating_sub(str_len);
+                write!(self.out, "{:remaining_space$}{}", "", result.value())?;
+                let mark = match verification {
+                    Verification::Unknown => " ".normal(),
+                    Verification::Correct => "✔".bright_green(),
+                    Verification::Incorrect => "✘".bright_red().bold(),
+                    Verification::Recorded => "✎".bright_blue(),
+                };
+                write!(self.out, " {mark} {}", format_duration(elapsed).dimmed())?;
+                _ = self.out.flush();
+            }
+            Format::Json | Format::Csv => {
+                self.write_record(answer, error, verification, elapsed)?
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares the answer of the current part against the recorded one, and
+    /// counts a mismatch. A missing answer, such as of a part that failed,
+    /// doesn't match a recorded one.
+    fn verify(&mut self, answer: Option<&str>) -> Verification {
+        let verification = match &mut self.answers {
+            Some(answers) => answers.verify(self.part, answer, self.record),
+            None => Verification::Unknown,
+        };
+        if verification == Verification::Incorrect {
+            self.mismatches += 1;
+        }
+        verification
+    }
+
+    /// Records that the current day failed before its parts ran, such as
+    /// when its input could not be read or parsed. In the pretty format, the
+    /// error is returned, in the structured formats each selected part gets a
+    /// record of it, and the remaining days still run.
+    pub fn fail_day(
+        &mut self,
+        day: u32,
+        parts: &[&'static str],
+        selected_parts: &[bool],
+        error: Error,
+    ) -> Result<()> {
+        if self.format == Format::Pretty {
+            return Err(error);
+        }
+        self.load_answers(day)?;
+        let selected = parts.iter().zip(selected_parts).filter(|(_, &s)| s);
+        for (&part, _) in selected {
+            self.part = part;
+            let verification = self.verify(None);
+            let error = Some(error.variant_name());
+            self.write_record(None, error, verification, Duration::ZERO)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the record of the current part in a structured format.
+    fn write_record(
+        &mut self,
+        answer: Option<&str>,
+        error: Option<&str>,
+        verification: Verification,
+        elapsed: Duration,
+    ) -> Result<()> {
+        let verified = match verification {
+            Verification::Correct => Some(true),
+            Verification::Incorrect => Some(false),
+            Verification::Unknown | Verification::Recorded => None,
+        };
+        match self.format {
+            Format::Pretty => unreachable!("the pretty format has no records"),
+            Format::Json => {
+                write!(self.out, "{{\"day\":{},\"part\":", self.day)?;
+                write_json_str(self.out, self.part)?;
+                write!(self.out, ",\"answer\":")?;
+                write_json_opt_str(self.out, answer)?;
+                write!(self.out, ",\"error\":")?;
+                write_json_opt_str(self.out, error)?;
+                match verified {
+                    Some(verified) => write!(self.out, ",\"verified\":{verified}")?,
+                    None => write!(self.out, ",\"verified\":null")?,
+                }
+                writeln!(self.out, ",\"elapsed_ns\":{}}}", elapsed.as_nanos())?;
+            }
+            Format::Csv => {
+                write!(self.out, "{},", self.day)?;
+                write_csv_str(self.out, self.part)?;
+                write!(self.out, ",")?;
+                write_csv_str(self.out, answer.unwrap_or(""))?;
+                write!(self.out, ",")?;
+                write_csv_str(self.out, error.unwrap_or(""))?;
+                match verified {
+                    Some(verified) => write!(self.out, ",{verified}")?,
+                    None => write!(self.out, ",")?,
+                }
+                writeln!(self.out, ",{}", elapsed.as_nanos())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats a duration with a unit that keeps it readable, padded to a fixed
+/// width so that subsequent columns line up.
+fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    let (value, unit) = match nanos {
+        0..=999 => return format!("{nanos:>6}ns"),
+        1_000..=999_999 => (nanos as f64 / 1e3, "µs"),
+        1_000_000..=999_999_999 => (nanos as f64 / 1e6, "ms"),
+        _ => (nanos as f64 / 1e9, "s "),
+    };
+    format!("{value:>6.2}{unit}")
+}
+
+fn split_result(result: &Result<ColoredOutput>) -> (Option<&str>, Option<&str>) {
+    match result {
+        Ok(output) => (Some(output.plain()), None),
+        Err(err) => (None, Some(err.variant_name())),
+    }
+}
+
+fn write_json_opt_str(out: &mut dyn Write, value: Option<&str>) -> std::io::Result<()> {
+    match value {
+        Some(value) => write_json_str(out, value),
+        None => write!(out, "null"),
+    }
+}
+
+fn write_json_str(out: &mut dyn Write, value: &str) -> std::io::Result<()> {
+    write!(out, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(out, "\\\"")?,
+            '\\' => write!(out, "\\\\")?,
+            '\n' => write!(out, "\\n")?,
+            '\r' => write!(out, "\\r")?,
+            '\t' => write!(out, "\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => write!(out, "{c}")?,
+        }
+    }
+    write!(out, "\"")
+}
+
+fn write_csv_str(out: &mut dyn Write, value: &str) -> std::io::Result<()> {
+    if value.contains([',', '"', '\n', '\r']) {
+        write!(out, "\"{}\"", value.replace('"', "\"\""))
+    } else {
+        write!(out, "{value}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(format: Format) -> String {
+        let answers_dir =
+            std::env::temp_dir().join(format!("aoc_report_{}_{format:?}", std::process::id()));
+        let mut answers = Answers::load(&answers_dir, 7).unwrap();
+        answers.insert("pt1", "a,\"b\"");
+        answers.save().unwrap();
+
+        let mut buffer = Vec::new();
+        let options = Options {
+            format,
+            ..Options::default()
+        };
+        let mut report =
+            Report::new(2017, &options, true, &mut buffer).with_answers_dir(&answers_dir);
+        report.begin().unwrap();
+        report.begin_day(7).unwrap();
+        report.begin_part("pt1").unwrap();
+        let answer = Ok(ColoredOutput::from("a,\"b\""));
+        report.end_part(answer, Duration::from_nanos(15)).unwrap();
+        report.begin_part("pt2").unwrap();
+        let error = Err(Error::NoSolution);
+        report.end_part(error, Duration::from_nanos(20)).unwrap();
+        report.end_day().unwrap();
+        let parts = ["pt1", "pt2", "pt3"];
+        let error = Error::invalid_input("no");
+        report
+            .fail_day(8, &parts, &[true, false, true], error)
+            .unwrap();
+        report.end().unwrap();
+        drop(report);
+        std::fs::remove_dir_all(&answers_dir).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn failed_day_mismatches() {
+        let answers_dir =
+            std::env::temp_dir().join(format!("aoc_report_{}_failed", std::process::id()));
+        let mut answers = Answers::load(&answers_dir, 1).unwrap();
+        answers.insert("pt1", "42");
+        answers.save().unwrap();
+
+        let mut buffer = Vec::new();
+        let options = Options {
+            format: Format::Json,
+            check: true,
+            ..Options::default()
+        };
+        let mut report =
+            Report::new(2017, &options, true, &mut buffer).with_answers_dir(&answers_dir);
+        report.begin().unwrap();
+        let error = Error::invalid_input("no");
+        report
+            .fail_day(1, &["pt1", "pt2"], &[true, true], error)
+            .unwrap();
+        let result = report.end();
+        drop(report);
+        std::fs::remove_dir_all(&answers_dir).unwrap();
+
+        assert!(matches!(result, Err(Error::AnswerMismatch(1))));
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(
+            "\"part\":\"pt1\",\"answer\":null,\"error\":\"InvalidInput\",\"verified\":false"
+        ));
+        assert!(output.contains(
+            "\"part\":\"pt2\",\"answer\":null,\"error\":\"InvalidInput\",\"verified\":null"
+        ));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!("    15ns", format_duration(Duration::from_nanos(15)));
+        assert_eq!("  1.50µs", format_duration(Duration::from_nanos(1_500)));
+        assert_eq!("999.99ms", format_duration(Duration::from_micros(999_990)));
+        assert_eq!(" 12.35s ", format_duration(Duration::from_millis(12_345)));
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            "\
+{\"day\":7,\"part\":\"pt1\",\"answer\":\"a,\\\"b\\\"\",\"error\":null,\"verified\":true,\"elapsed_ns\":15}
+{\"day\":7,\"part\":\"pt2\",\"answer\":null,\"error\":\"NoSolution\",\"verified\":null,\"elapsed_ns\":20}
+{\"day\":8,\"part\":\"pt1\",\"answer\":null,\"error\":\"InvalidInput\",\"verified\":null,\"elapsed_ns\":0}
+{\"day\":8,\"part\":\"pt3\",\"answer\":null,\"error\":\"InvalidInput\",\"verified\":null,\"elapsed_ns\":0}
+",
+            run(Format::Json)
+        );
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            "\
+day,part,answer,error,verified,elapsed_ns
+7,pt1,\"a,\"\"b\"\"\",,true,15
+7,pt2,,NoSolution,,20
+8,pt1,,InvalidInput,,0
+8,pt3,,InvalidInput,,0
+",
+            run(Format::Csv)
+        );
+    }
+}
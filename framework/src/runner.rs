@@ -1,75 +1,246 @@
 pub use crate::{
     inputs::Inputs,
+    options::Options,
     outputs::ColoredOutput,
+    report::Report,
     result::{IntoResult, Result},
 };
 pub use colored::Colorize;
 pub use std::io::Write;
 
+use crate::{error::Error, report::Summary};
+use std::{collections::BTreeMap, sync::mpsc, thread, time::Instant};
+
+/// Entry points of a single day, as generated by the `day!` macro.
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    pub number: u32,
+    pub parts: &'static [&'static str],
+    pub is_slow: bool,
+    /// Parses the input, and executes the parts that are marked as selected.
+    pub execute: fn(&[u8], &[bool], &mut Report<'_>) -> Result<()>,
+}
+
+/// A day that is selected to run, along with which of its parts are selected.
+type SelectedDay<'d> = (&'d Day, Vec<bool>);
+
+/// Runs all days of a year selected by the command line arguments.
+pub fn run(year: u32, days: &[Day]) -> Result<()> {
+    let options = Options::from_args(std::env::args().skip(1))?;
+    let selection = &options.selection;
+    selection.validate(days.iter().map(|day| (day.number, day.parts.len())))?;
+    let days = days
+        .iter()
+        .map(|day| {
+            let parts = (1..=day.parts.len())
+                .map(|part| selection.includes_part(day.number, part, day.is_slow))
+                .collect::<Vec<_>>();
+            (day, parts)
+        })
+        .filter(|(_, parts)| parts.contains(&true))
+        .collect::<Vec<SelectedDay>>();
+
+    if options.input.is_some() && days.len() != 1 {
+        return Err(Error::InvalidArgument(
+            "an explicit input requires exactly one day to be selected".into(),
+        ));
+    }
+
+    let mut inputs = Inputs::new(year);
+    if let Some(directory) = &options.input_dir {
+        inputs = inputs.with_directory(directory);
+    }
+    if let Some(source) = &options.input {
+        inputs = inputs.with_source(source.clone());
+    }
+    if options.offline {
+        inputs = inputs.offline();
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    let mut report = Report::new(year, &options, inputs.are_puzzle_inputs(), &mut stdout);
+    report.begin()?;
+    if options.parallel {
+        run_parallel(year, &days, &options, &mut inputs, &mut report)?;
+    } else {
+        for (day, parts) in days {
+            match inputs.get(day.number) {
+                Ok(input) => (day.execute)(&input, &parts, &mut report)?,
+                Err(error) => report.fail_day(day.number, day.parts, &parts, error)?,
+            }
+        }
+    }
+    report.end()
+}
+
+/// Prints the error, if any, with its sources, so that errors with a
+/// multi-line description (such as parse errors) are readable.
+pub fn exit_code(result: Result<()>) -> std::process::ExitCode {
+    match result {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e.chain());
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the days on a pool of worker threads. Each day writes its output to
+/// a buffer, which is printed as soon as all preceding days have finished.
+fn run_parallel(
+    year: u32,
+    days: &[SelectedDay],
+    options: &Options,
+    inputs: &mut Inputs,
+    report: &mut Report,
+) -> Result<()> {
+    let puzzle_inputs = inputs.are_puzzle_inputs();
+    // Fetch all inputs up-front, so that downloads stay sequential. The days
+    // without an input are reported here, and skipped by the workers.
+    let mut failures = Vec::new();
+    let inputs = days
+        .iter()
+        .enumerate()
+        .map(|(index, (day, _))| match inputs.get(day.number) {
+            Ok(input) => Some(input),
+            Err(error) => {
+                failures.push((index, error));
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let worker_count = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(days.len());
+    let next_index = std::sync::atomic::AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel::<(usize, Vec<u8>, Summary, Result<()>)>();
+    for (index, error) in failures {
+        let (day, parts) = &days[index];
+        let (buffer, summary, result) = buffered_report(year, options, puzzle_inputs, |report| {
+            report.fail_day(day.number, day.parts, parts, error)
+        });
+        _ = sender.send((index, buffer, summary, result));
+    }
+
+    // The days overlap, so the total is the time they take together.
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..worker_count {
+            let sender = sender.clone();
+            let (next_index, inputs) = (&next_index, &inputs);
+            scope.spawn(move || loop {
+                let index = next_index.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                let Some((day, parts)) = days.get(index) else {
+                    break;
+                };
+                let Some(input) = &inputs[index] else {
+                    continue;
+                };
+                let (buffer, summary, result) =
+                    buffered_report(year, options, puzzle_inputs, |report| {
+                        (day.execute)(input, parts, report)
+                    });
+                if sender.send((index, buffer, summary, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut next_to_print = 0;
+        for (index, buffer, summary, result) in receiver {
+            pending.insert(index, (buffer, summary, result));
+            while let Some((buffer, summary, result)) = pending.remove(&next_to_print) {
+                report.append(&buffer, summary)?;
+                result?;
+                next_to_print += 1;
+            }
+        }
+        Ok::<_, Error>(())
+    })?;
+    report.add_elapsed(start.elapsed());
+    Ok(())
+}
+
+/// Runs `f` with a report of its own, and returns the output it wrote along
+/// with its statistics.
+fn buffered_report(
+    year: u32,
+    options: &Options,
+    puzzle_inputs: bool,
+    f: impl FnOnce(&mut Report) -> Result<()>,
+) -> (Vec<u8>, Summary, Result<()>) {
+    let mut buffer = Vec::new();
+    let mut report = Report::new(year, options, puzzle_inputs, &mut buffer);
+    let result = f(&mut report);
+    let summary = report.summary();
+    (buffer, summary, result)
+}
+
+/// Declares the day modules of a year, and a `main` function that runs them.
 #[macro_export]
 macro_rules! main {
-    ($($day:ident),*$(,)?) => {
+    ($year:literal; $($day:ident),*$(,)?) => {
         $(mod $day;)*
 
-        fn main() -> $crate::runner::Result<()> {
-            use $crate::runner::*;
-            println!(
-                "\n🎄 {} {} {} {} 🎄\n",
-                "Advent".bright_red().bold(),
-                "of".bright_green(),
-                "Code".blue().bold(),
-                "2017".bright_magenta().bold()
-            );
-
-            let included_days: Vec<u32> = std::env::args()
-                .filter_map(|v| v.parse::<u32>().ok())
-                .collect();
-
-            let mut inputs = Inputs::new();
-            $({
-                if included_days.is_empty() || included_days.contains(&$day::DayMetadata::number()) {
-                    $day::DayMetadata::execute(&mut inputs)?;
-                }
-            })*
-            println!();
-            Ok(())
+        /// The year of the days in this crate.
+        pub const YEAR: u32 = $year;
+
+        fn main() -> std::process::ExitCode {
+            let result = $crate::runner::run(YEAR, &[$(
+                $crate::runner::Day {
+                    number: $day::DayMetadata::number(),
+                    parts: $day::DayMetadata::parts(),
+                    is_slow: $day::DayMetadata::is_slow(),
+                    execute: $day::DayMetadata::execute,
+                },
+            )*]);
+            $crate::runner::exit_code(result)
         }
     };
 }
 
+/// Declares the parse and part functions of a day. Days that take a long
+/// time to run can be marked as `slow`, so they can be skipped with
+/// `--skip-slow`. The benchmarks use the `YEAR` constant declared by `main!`.
 #[macro_export]
+#[allow(clippy::crate_in_macro_def)]
 macro_rules! day {
+    ($day_nr:literal, $parse_fn:ident => $($part_fn:ident),+; slow) => {
+        $crate::day!(@impl true, $day_nr, $parse_fn => $($part_fn),+);
+    };
     ($day_nr:literal, $parse_fn:ident => $($part_fn:ident),+) => {
+        $crate::day!(@impl false, $day_nr, $parse_fn => $($part_fn),+);
+    };
+    (@impl $is_slow:literal, $day_nr:literal, $parse_fn:ident => $($part_fn:ident),+) => {
 use super::prelude::*;
 pub struct DayMetadata;
 impl DayMetadata {
     pub fn number() -> u32 { $day_nr }
-    pub fn execute(inputs: &mut $crate::runner::Inputs) -> $crate::runner::Result<()> {
+    pub fn parts() -> &'static [&'static str] { &[$(stringify!($part_fn)),+] }
+    pub fn is_slow() -> bool { $is_slow }
+    pub fn execute(input: &[u8], selected_parts: &[bool], report: &mut $crate::runner::Report<'_>) -> $crate::runner::Result<()> {
         use $crate::runner::*;
-        const OUTPUT_WIDTH: usize = 40;
-        print!(
-            "{} {}",
-            "Day".bright_blue(),
-            format!("{:>2}", $day_nr).bright_red().bold()
-        );
-
-        let input = inputs.get($day_nr)?;
-        let parsed = $parse_fn(&input)?;
-        $({
-            let part_name = stringify!($part_fn);
-            let remaining_space = OUTPUT_WIDTH.checked_sub(part_name.len() + 1).unwrap_or(0);
-            print!(" {} {} ", "::".magenta(), part_name.bright_yellow());
-            _ = std::io::stdout().flush();
-            let result: ColoredOutput = IntoResult::into_result($part_fn(&parsed))?.into();
-            let str_len = result.value().len() - result.control_count();
-            let remaining_space = remaining_space.checked_sub(str_len).unwrap_or(0);
-            for _ in 0..remaining_space {
-                print!(" ");
-            }
-            print!("{}", result.value());
-            _ = std::io::stdout().flush();
+        report.begin_day($day_nr)?;
+
+        let start = std::time::Instant::now();
+        let parsed = match $parse_fn(input) {
+            Ok(parsed) => parsed,
+            Err(error) => return report.fail_day($day_nr, Self::parts(), selected_parts, error.into()),
+        };
+        report.end_parse(start.elapsed())?;
+        let mut is_selected = selected_parts.iter().copied();
+        $(if is_selected.next().unwrap_or(true) {
+            report.begin_part(stringify!($part_fn))?;
+            let start = std::time::Instant::now();
+            let result = IntoResult::into_result($part_fn(&parsed));
+            let elapsed = start.elapsed();
+            report.end_part(result.map(ColoredOutput::from), elapsed)?;
         })+
-        println!();
+        report.end_day()?;
 
         Ok(())
     }
@@ -79,7 +250,7 @@ $crate::paste! {
     #[criterion_macro::criterion]
     pub fn benchmarks(c: &mut criterion::Criterion) {
         use criterion::{black_box, Criterion};
-        let mut inputs = $crate::inputs::Inputs::new();
+        let mut inputs = $crate::inputs::Inputs::new(crate::YEAR);
         let input = inputs.get($day_nr).expect("could not get input");
         let parsed = $parse_fn(&input).expect("could not parse input");
         c.bench_function(stringify!([<day $day_nr _ $parse_fn>]), |b| b.iter(|| $parse_fn(&input)));
@@ -0,0 +1,169 @@
+use crate::{error::Error, parsers::*, result::Result};
+use std::ops::RangeInclusive;
+
+/// A day, a range of days, or a single part of a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Target {
+    days: RangeInclusive<u32>,
+    /// One-based index of the part, `None` selects all parts.
+    part: Option<usize>,
+}
+
+impl Target {
+    fn matches(&self, day: u32, part: usize) -> bool {
+        self.days.contains(&day) && (self.part.is_none() || self.part == Some(part))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Suffix {
+    Range(u32),
+    Part(usize),
+}
+
+/// Which days and parts to run. Without any included targets, all days are
+/// included.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    included: Vec<Target>,
+    excluded: Vec<Target>,
+    pub skip_slow: bool,
+}
+
+impl Selection {
+    /// Adds a target of the form `12` (a day), `12.2` (a part of a day) or
+    /// `3-7` (a range of days). Prefixing it with `!` excludes it instead.
+    pub fn add(&mut self, arg: &str) -> Result<()> {
+        let invalid = || Error::InvalidArgument(format!("unrecognized argument {arg}"));
+        let suffix = token(b'-')
+            .then(number::<u32>())
+            .map(Suffix::Range)
+            .or(token(b'.').then(number::<usize>()).map(Suffix::Part));
+        let target = token(b'!').opt().and(number::<u32>()).and(suffix.opt());
+        let ((exclude, day), suffix) = target
+            .parse(arg.as_bytes())
+            .ok()
+            .filter(|(_, remainder)| remainder.is_empty())
+            .ok_or_else(invalid)?
+            .0;
+
+        let target = match suffix {
+            None => Target {
+                days: day..=day,
+                part: None,
+            },
+            Some(Suffix::Range(end)) if day <= end => Target {
+                days: day..=end,
+                part: None,
+            },
+            Some(Suffix::Part(part)) if part > 0 => Target {
+                days: day..=day,
+                part: Some(part),
+            },
+            _ => return Err(invalid()),
+        };
+        if exclude.is_some() {
+            self.excluded.push(target);
+        } else {
+            self.included.push(target);
+        }
+        Ok(())
+    }
+
+    /// Checks that every target refers to days and parts that exist, given
+    /// the day numbers with their part count.
+    pub fn validate<I: IntoIterator<Item = (u32, usize)>>(&self, days: I) -> Result<()> {
+        let days = days.into_iter().collect::<Vec<_>>();
+        for target in self.included.iter().chain(&self.excluded) {
+            let (start, end) = (*target.days.start(), *target.days.end());
+            let mut matching = days
+                .iter()
+                .filter(|(day, _)| target.days.contains(day))
+                .peekable();
+            if matching.peek().is_none() {
+                return Err(Error::InvalidArgument(if start == end {
+                    format!("there is no day {start}")
+                } else {
+                    format!("there are no days in {start}-{end}")
+                }));
+            }
+            if let Some(part) = target.part {
+                if matching.any(|&(_, part_count)| part > part_count) {
+                    return Err(Error::InvalidArgument(format!(
+                        "day {start} has no part {part}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn includes_part(&self, day: u32, part: usize, is_slow: bool) -> bool {
+        let is_included =
+            self.included.is_empty() || self.included.iter().any(|t| t.matches(day, part));
+        let is_excluded =
+            (self.skip_slow && is_slow) || self.excluded.iter().any(|t| t.matches(day, part));
+        is_included && !is_excluded
+    }
+
+    pub fn includes_day(&self, day: u32, part_count: usize, is_slow: bool) -> bool {
+        (1..=part_count).any(|part| self.includes_part(day, part, is_slow))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn selection(args: &[&str]) -> Result<Selection> {
+        let mut selection = Selection::default();
+        for arg in args {
+            selection.add(arg)?;
+        }
+        Ok(selection)
+    }
+
+    #[test]
+    fn grammar() {
+        let s = selection(&["3-7", "12.2", "!5"]).unwrap();
+        assert!(s.includes_day(3, 2, false));
+        assert!(!s.includes_day(5, 2, false));
+        assert!(s.includes_day(7, 2, false));
+        assert!(!s.includes_day(8, 2, false));
+        assert!(!s.includes_part(12, 1, false));
+        assert!(s.includes_part(12, 2, false));
+
+        let s = selection(&["!21", "!4.1"]).unwrap();
+        assert!(s.includes_day(1, 2, false));
+        assert!(!s.includes_day(21, 2, false));
+        assert!(!s.includes_part(4, 1, false));
+        assert!(s.includes_part(4, 2, false));
+
+        for invalid in ["", "x", "3-", "7-3", "12.0", "12.2.1", "!", "1,2", "--foo"] {
+            assert!(
+                selection(&[invalid]).is_err(),
+                "{invalid:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn skip_slow() {
+        let mut s = selection(&["15"]).unwrap();
+        s.skip_slow = true;
+        assert!(!s.includes_day(15, 2, true));
+        assert!(!s.includes_day(16, 2, false));
+    }
+
+    #[test]
+    fn validation() {
+        let days = [(1, 2), (2, 2), (25, 1)];
+        assert!(selection(&["1.2", "2", "1-30"])
+            .unwrap()
+            .validate(days)
+            .is_ok());
+        assert!(selection(&["25.2"]).unwrap().validate(days).is_err());
+        assert!(selection(&["!3"]).unwrap().validate(days).is_err());
+        assert!(selection(&["3-24"]).unwrap().validate(days).is_err());
+    }
+}
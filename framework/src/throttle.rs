@@ -0,0 +1,161 @@
+use crate::result::Result;
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Source of the current time, which can be replaced in tests.
+pub trait Clock: std::fmt::Debug {
+    fn now(&self) -> SystemTime;
+    fn sleep(&self, duration: Duration);
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Enforces a minimum interval between requests. The time of the last
+/// request is stored in a file, so the interval also holds across runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throttle {
+    min_interval: Duration,
+    timestamp_path: Option<PathBuf>,
+    last_time: Option<SystemTime>,
+}
+
+impl Throttle {
+    pub fn new(min_interval: Duration, timestamp_path: Option<PathBuf>) -> Throttle {
+        Throttle {
+            min_interval,
+            timestamp_path,
+            last_time: None,
+        }
+    }
+
+    pub fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    pub fn set_min_interval(&mut self, min_interval: Duration) {
+        self.min_interval = min_interval;
+    }
+
+    /// Sleeps until the minimum interval since the last request has passed,
+    /// then marks the current time as the time of the last request.
+    pub fn wait(&mut self, clock: &dyn Clock) -> Result<()> {
+        let last_time = self.last_time.max(self.read_timestamp()?);
+        if let Some(last_time) = last_time {
+            // A clock that went backwards yields an error, which is also a
+            // reason not to wait.
+            if let Ok(remaining) = (last_time + self.min_interval).duration_since(clock.now()) {
+                clock.sleep(remaining);
+            }
+        }
+
+        let now = clock.now();
+        self.last_time = Some(now);
+        if let Some(path) = &self.timestamp_path {
+            let millis = now
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            std::fs::write(path, millis.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Reads the time of the last request from another run, an absent or
+    /// unreadable timestamp is ignored.
+    fn read_timestamp(&self) -> Result<Option<SystemTime>> {
+        let Some(path) = &self.timestamp_path else {
+            return Ok(None);
+        };
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(contents
+            .trim()
+            .parse()
+            .ok()
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis)))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    /// A clock that only advances when sleeping, and remembers every sleep.
+    #[derive(Debug, Clone)]
+    pub(crate) struct FakeClock(Rc<RefCell<(SystemTime, Vec<Duration>)>>);
+
+    impl FakeClock {
+        pub(crate) fn new(secs: u64) -> FakeClock {
+            FakeClock(Rc::new(RefCell::new((
+                UNIX_EPOCH + Duration::from_secs(secs),
+                Vec::new(),
+            ))))
+        }
+
+        pub(crate) fn advance(&self, duration: Duration) {
+            self.0.borrow_mut().0 += duration;
+        }
+
+        pub(crate) fn take_sleeps(&self) -> Vec<Duration> {
+            std::mem::take(&mut self.0.borrow_mut().1)
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> SystemTime {
+            self.0.borrow().0
+        }
+
+        fn sleep(&self, duration: Duration) {
+            let mut state = self.0.borrow_mut();
+            state.0 += duration;
+            state.1.push(duration);
+        }
+    }
+
+    #[test]
+    fn throttle() {
+        let path = std::env::temp_dir().join(format!("aoc_timestamp_{}", std::process::id()));
+        let clock = FakeClock::new(1_000);
+        let secs = Duration::from_secs;
+
+        let mut throttle = Throttle::new(secs(3), Some(path.clone()));
+        throttle.wait(&clock).unwrap();
+        assert_eq!(Vec::<Duration>::new(), clock.take_sleeps());
+        clock.advance(secs(1));
+        throttle.wait(&clock).unwrap();
+        assert_eq!(vec![secs(2)], clock.take_sleeps());
+        clock.advance(secs(5));
+        throttle.wait(&clock).unwrap();
+        assert_eq!(Vec::<Duration>::new(), clock.take_sleeps());
+
+        // Another run picks up the timestamp of the previous one.
+        clock.advance(Duration::from_millis(500));
+        let mut throttle = Throttle::new(secs(3), Some(path.clone()));
+        throttle.wait(&clock).unwrap();
+        assert_eq!(vec![Duration::from_millis(2_500)], clock.take_sleeps());
+
+        std::fs::write(&path, "garbage").unwrap();
+        let mut throttle = Throttle::new(secs(3), Some(path.clone()));
+        throttle.wait(&clock).unwrap();
+        assert_eq!(Vec::<Duration>::new(), clock.take_sleeps());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt::Display,
+    hash::Hash,
+    ops::Index,
+};
+
+use thiserror::Error;
+
+use crate::error::Error;
+
+/// A tree of nodes identified by a key, such as a name, stored in an arena
+/// and referred to by their index in it.
+#[derive(Debug, Clone)]
+pub struct Tree<K, T> {
+    nodes: Vec<Node<K, T>>,
+    indices: HashMap<K, usize>,
+    root: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Node<K, T> {
+    pub key: K,
+    pub value: T,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+/// Why records don't form a single tree.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TreeError<K> {
+    #[error("no nodes")]
+    Empty,
+    #[error("`{0}` is listed more than once")]
+    Duplicate(K),
+    #[error("`{child}`, a child of `{parent}`, is not listed")]
+    MissingChild { parent: K, child: K },
+    #[error("`{child}` is a child of both `{first}` and `{second}`")]
+    MultipleParents { child: K, first: K, second: K },
+    /// Nodes without a parent, the root and any orphans.
+    #[error("multiple roots: {}", join(.0))]
+    MultipleRoots(Vec<K>),
+    /// A node that can't be reached from the root, as its ancestors form a
+    /// cycle.
+    #[error("`{0}` is part of a cycle")]
+    Cycle(K),
+}
+
+fn join<K: Display>(keys: &[K]) -> String {
+    let keys: Vec<_> = keys.iter().map(|k| format!("`{k}`")).collect();
+    keys.join(", ")
+}
+
+impl<K> TreeError<K> {
+    /// Converts the keys, such as to something that can be displayed.
+    pub fn map_keys<L>(self, mut f: impl FnMut(K) -> L) -> TreeError<L> {
+        use TreeError::*;
+        match self {
+            Empty => Empty,
+            Duplicate(key) => Duplicate(f(key)),
+            MissingChild { parent, child } => MissingChild {
+                parent: f(parent),
+                child: f(child),
+            },
+            MultipleParents {
+                child,
+                first,
+                second,
+            } => MultipleParents {
+                child: f(child),
+                first: f(first),
+                second: f(second),
+            },
+            MultipleRoots(keys) => MultipleRoots(keys.into_iter().map(f).collect()),
+            Cycle(key) => Cycle(f(key)),
+        }
+    }
+}
+
+impl<K: Display> From<TreeError<K>> for Error {
+    fn from(error: TreeError<K>) -> Self {
+        Error::invalid_input(format!("invalid tree: {error}"))
+    }
+}
+
+impl<K: Eq + Hash + Clone, T> Tree<K, T> {
+    /// Builds a tree out of records of a key, a value, and the keys of the
+    /// children. The records must form exactly one tree: every child must be
+    /// listed, and all nodes but the root must have exactly one parent.
+    pub fn build<C>(records: impl IntoIterator<Item = (K, T, C)>) -> Result<Self, TreeError<K>>
+    where
+        C: IntoIterator<Item = K>,
+    {
+        let mut nodes = Vec::new();
+        let mut indices = HashMap::new();
+        let mut children_keys = Vec::new();
+        for (key, value, children) in records {
+            match indices.entry(key) {
+                Entry::Occupied(slot) => return Err(TreeError::Duplicate(slot.key().clone())),
+                Entry::Vacant(slot) => {
+                    nodes.push(Node {
+                        key: slot.key().clone(),
+                        value,
+                        parent: None,
+                        children: Vec::new(),
+                    });
+                    slot.insert(nodes.len() - 1);
+                }
+            }
+            children_keys.push(children);
+        }
+
+        for (parent, children) in children_keys.into_iter().enumerate() {
+            for child_key in children {
+                let Some(&child) = indices.get(&child_key) else {
+                    return Err(TreeError::MissingChild {
+                        parent: nodes[parent].key.clone(),
+                        child: child_key,
+                    });
+                };
+                if let Some(first) = nodes[child].parent {
+                    return Err(TreeError::MultipleParents {
+                        child: child_key,
+                        first: nodes[first].key.clone(),
+                        second: nodes[parent].key.clone(),
+                    });
+                }
+                nodes[child].parent = Some(parent);
+                nodes[parent].children.push(child);
+            }
+        }
+
+        let mut roots = nodes.iter().enumerate().filter(|(_, n)| n.parent.is_none());
+        let root = match (roots.next(), roots.next()) {
+            (Some((root, _)), None) => root,
+            (Some(_), Some(_)) => {
+                let roots = nodes.iter().filter(|n| n.parent.is_none());
+                return Err(TreeError::MultipleRoots(
+                    roots.map(|n| n.key.clone()).collect(),
+                ));
+            }
+            // With every node having a parent, the first one is in a cycle.
+            (None, _) => {
+                return Err(nodes
+                    .first()
+                    .map_or(TreeError::Empty, |n| TreeError::Cycle(n.key.clone())))
+            }
+        };
+
+        let tree = Tree {
+            nodes,
+            indices,
+            root,
+        };
+        let mut reached = vec![false; tree.nodes.len()];
+        for index in tree.post_order() {
+            reached[index] = true;
+        }
+        match reached.iter().position(|&reached| !reached) {
+            Some(index) => Err(TreeError::Cycle(tree.nodes[index].key.clone())),
+            None => Ok(tree),
+        }
+    }
+
+    pub fn index_of(&self, key: &K) -> Option<usize> {
+        self.indices.get(key).copied()
+    }
+}
+
+impl<K, T> Tree<K, T> {
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    /// All nodes, in the order of their records.
+    pub fn nodes(&self) -> &[Node<K, T>] {
+        &self.nodes
+    }
+
+    /// The indices of all nodes, with the children of a node, in order,
+    /// before the node itself.
+    pub fn post_order(&self) -> Vec<usize> {
+        // Visiting the children in reverse before the node itself, reversed.
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![self.root];
+        while let Some(index) = stack.pop() {
+            order.push(index);
+            stack.extend(&self.nodes[index].children);
+        }
+        order.reverse();
+        order
+    }
+
+    /// Computes an aggregate of each subtree, out of its root node and the
+    /// aggregates of its children, like the total weight of a subtree.
+    /// Returns the aggregates, by index of the node.
+    pub fn fold<A>(&self, mut f: impl FnMut(&Node<K, T>, &[&A]) -> A) -> Vec<A> {
+        let mut aggregates: Vec<Option<A>> = (0..self.nodes.len()).map(|_| None).collect();
+        for index in self.post_order() {
+            let node = &self.nodes[index];
+            let children: Vec<&A> = node
+                .children
+                .iter()
+                .map(|&child| aggregates[child].as_ref().unwrap())
+                .collect();
+            let aggregate = f(node, &children);
+            aggregates[index] = Some(aggregate);
+        }
+        aggregates.into_iter().map(Option::unwrap).collect()
+    }
+
+    /// Renders the tree as lines of the labels of the nodes, indented by their
+    /// depth, with the children of a node below it. The label is given the
+    /// index of the node, such as to look up its aggregate from `fold`.
+    pub fn render(&self, mut label: impl FnMut(usize, &Node<K, T>) -> String) -> String {
+        let mut text = String::new();
+        let mut stack = vec![(self.root, 0)];
+        while let Some((index, depth)) = stack.pop() {
+            let node = &self.nodes[index];
+            text.push_str(&"  ".repeat(depth));
+            text.push_str(&label(index, node));
+            text.push('\n');
+            let children = node.children.iter().rev();
+            stack.extend(children.map(|&child| (child, depth + 1)));
+        }
+        text
+    }
+}
+
+impl<K, T> Index<usize> for Tree<K, T> {
+    type Output = Node<K, T>;
+
+    fn index(&self, index: usize) -> &Node<K, T> {
+        &self.nodes[index]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type Record = (&'static str, u32, &'static [&'static str]);
+
+    fn build(records: &[Record]) -> Result<Tree<&'static str, u32>, String> {
+        let records = records
+            .iter()
+            .map(|&(key, value, children)| (key, value, children.iter().copied()));
+        Tree::build(records).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn trees() {
+        let tree = build(&[
+            ("b", 2, &[]),
+            ("a", 1, &["b", "c"]),
+            ("c", 3, &["d"]),
+            ("d", 4, &[]),
+        ])
+        .unwrap();
+        assert_eq!("a", tree[tree.root()].key);
+        assert_eq!(vec![0, 3, 2, 1], tree.post_order());
+        let totals = tree.fold(|node, children| node.value + children.iter().copied().sum::<u32>());
+        assert_eq!(vec![2, 10, 7, 4], totals);
+        let c = tree.index_of(&"c").unwrap();
+        assert_eq!(Some(1), tree[c].parent);
+
+        let rendered = tree.render(|index, node| format!("{} ({})", node.key, totals[index]));
+        assert_eq!("a (10)\n  b (2)\n  c (7)\n    d (4)\n", rendered);
+
+        #[rustfmt::skip]
+        let errors: [(&[Record], &str); 6] = [
+            (&[],                                                  "no nodes"),
+            (&[("a", 1, &[]), ("a", 2, &[])],                      "`a` is listed more than once"),
+            (&[("a", 1, &["b"])],                                  "`b`, a child of `a`, is not listed"),
+            (&[("a", 1, &["c"]), ("b", 2, &["c"]), ("c", 3, &[])], "`c` is a child of both `a` and `b`"),
+            (&[("a", 1, &[]), ("b", 2, &[]), ("c", 3, &["a"])],    "multiple roots: `b`, `c`"),
+            (&[("a", 1, &[]), ("b", 2, &["c"]), ("c", 3, &["b"])], "`b` is part of a cycle"),
+        ];
+        for (records, expected) in errors {
+            assert_eq!(Err(expected.to_owned()), build(records).map(|_| ()));
+        }
+    }
+}
@@ -24,7 +24,7 @@ fn pt2(input: &[Vec<u32>]) -> Result<u32> {
                 .filter(|(min, max)| max % min == 0)
                 .map(|(min, max)| max / min)
                 .next()
-                .ok_or(Error::InvalidInput("no divisible items"))
+                .ok_or(Error::invalid_input("no divisible items"))
         })
         .sum()
 }
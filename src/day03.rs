@@ -4,7 +4,7 @@ type Vec2 = framework::vecs::Vec2<i32>;
 
 fn calculate_position(value: u32) -> Result<Vec2> {
     let mut value = match value {
-        0 => return Err(Error::InvalidInput("0 is invalid")),
+        0 => return Err(Error::invalid_input("0 is invalid")),
         1 => return Ok(Vec2::zero()),
         _ => value - 2,
     };
@@ -1,75 +1,58 @@
+use framework::tree::Tree;
+
 framework::day!(07, parse => pt1, pt2);
 
-fn get_root<'i>(input: &'i [Shoutout<'i>]) -> Result<&'i Shoutout<'i>> {
-    let seen = input
-        .iter()
-        .flat_map(|shoutout| shoutout.carrying.iter().flat_map(|vs| vs.iter()))
-        .collect::<HashSet<_>>();
-    input
-        .iter()
-        .find(|shoutout| !seen.contains(&shoutout.name))
-        .ok_or(Error::NoSolution)
-}
+/// The programs by name, with their weight.
+type Tower<'i> = Tree<&'i AStr, u32>;
 
-fn pt1<'i>(input: &'i [Shoutout<'i>]) -> Result<&'i AStr> {
-    get_root(input).map(|shoutout| shoutout.name)
+fn pt1<'i>(tower: &Tower<'i>) -> &'i AStr {
+    tower[tower.root()].key
 }
 
-fn pt2(input: &[Shoutout]) -> Result<i32> {
-    let node_map = input
-        .iter()
-        .map(|shoutout| (shoutout.name, shoutout))
-        .collect::<HashMap<_, _>>();
-
-    fn calc_weight(name: &AStr, node_map: &HashMap<&AStr, &Shoutout>) -> u32 {
-        let node = node_map.get(&name).unwrap();
-        let carried_weight = if let Some(carried) = &node.carrying {
-            carried.iter().map(|c| calc_weight(c, node_map)).sum()
-        } else {
-            0
-        };
-        node.weight + carried_weight
-    }
-    let calc_weight = |name| calc_weight(name, &node_map);
-    let mut current = get_root(input)?;
+fn pt2(tower: &Tower) -> Result<i32> {
+    // The weight of each program, including everything it carries.
+    let weights =
+        tower.fold(|program, carried| program.value + carried.iter().copied().sum::<u32>());
+    let mut current = tower.root();
     let mut required_adjustment = 0i32;
     loop {
-        let carrying = current.carrying.as_ref().unwrap();
-        let distinct = carrying.iter().map(|c| calc_weight(c)).find_distinct();
+        let carrying = &tower[current].children;
+        let distinct = carrying.iter().map(|&c| weights[c]).find_distinct();
         match distinct {
             DistinctResult::SingleDistinct(v) => {
-                current = node_map[carrying[v.index]];
+                current = carrying[v.index];
                 required_adjustment = v.common as i32 - v.distinct as i32;
             }
-            DistinctResult::Unique(_) => return Ok(current.weight as i32 + required_adjustment),
+            DistinctResult::Unique(_) => {
+                return Ok(tower[current].value as i32 + required_adjustment)
+            }
             _ => return Err(Error::NoSolution),
         }
     }
 }
 
-struct Shoutout<'i> {
-    name: &'i AStr,
-    weight: u32,
-    carrying: Option<Vec<&'i AStr>>,
-}
-
-fn parse(input: &[u8]) -> Result<Vec<Shoutout>> {
+fn parse(input: &[u8]) -> Result<Tower> {
     use parsers::*;
     let word = take_while((), |_, l| l.is_ascii_lowercase());
     let base = word
-        .trailed(token(b" ("))
+        .trailed(phrase(b" ( "))
         .and(number::<u32>())
-        .trailed(token(b')'));
-    let carrying = token(b" -> ").then(word.sep_by(token(b", "))).opt();
+        .trailed(phrase(b" )"));
+    let carrying = phrase(b" -> ")
+        .then(word.sep_by::<_, Vec<_>>(phrase(b", ")))
+        .opt();
 
     let shoutout = base
         .and(carrying)
-        .map(|((name, weight), carrying)| Shoutout {
-            name,
-            weight,
-            carrying,
-        });
-    shoutout.sep_by(token(b'\n')).execute(input)
+        .map(|((name, weight), carrying)| (name, weight, carrying.unwrap_or_default()));
+    let shoutouts: Vec<_> = ws()
+        .then(shoutout.sep_by(phrase(b"\n")))
+        .trailed(ws())
+        .execute(input)?;
+    Tree::build(shoutouts).map_err(|e| {
+        e.map_keys(|name: &AStr| String::from_utf8_lossy(name).into_owned())
+            .into()
+    })
 }
 
 tests! {
@@ -65,21 +65,21 @@ enum Condition {
 fn parse(input: &[u8]) -> Result<Vec<Instruction>> {
     use parsers::*;
     let register = pattern!(b'a'..=b'z').repeat_into::<Register>();
-    let inc = token(b" inc ").then(number::<i32>());
-    let dec = token(b" dec ").then(number::<i32>()).map(|n| -n);
+    let inc = phrase(b" inc ").then(number::<i32>());
+    let dec = phrase(b" dec ").then(number::<i32>()).map(|n| -n);
     let offset = inc.or(dec);
-    let condition = token(b" if ")
+    let condition = phrase(b" if ")
         .then(register.clone())
-        .trailed(token(b' '))
+        .trailed(ws())
         .and(
             pattern!(b'=' | b'>' | b'<' | b'!')
                 .fold_mut(ArrayString::<2>::new(), |v, l| v.push(l as char))
                 .map_res(|v| {
                     Condition::from_str(v.as_str())
-                        .map_err(|_| ParseError::Custom("expected condition"))
+                        .map_err(|_| ParseError::custom("expected condition"))
                 }),
         )
-        .trailed(token(b' '))
+        .trailed(ws())
         .and(number::<i32>())
         .map(|((register, condition), value)| (register, condition, value));
 
@@ -92,7 +92,9 @@ fn parse(input: &[u8]) -> Result<Vec<Instruction>> {
             condition,
         });
 
-    instruction.sep_by(token(b'\n')).execute(input)
+    ws().then(instruction.sep_by(phrase(b"\n")))
+        .trailed(ws())
+        .execute(input)
 }
 
 tests! {
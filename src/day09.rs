@@ -1,31 +1,28 @@
 framework::day!(09, parse => pt1, pt2);
 
 #[derive(Debug, Clone)]
-enum Stream<'i> {
-    GroupOpen,
-    GroupClose,
+enum Thing<'i> {
+    Group(Vec<Thing<'i>>),
     Garbage(&'i AStr),
 }
 
-fn pt1(input: &[Stream]) -> u32 {
-    input
-        .iter()
-        .fold((0, 0), |(score, nesting_level), value| match value {
-            Stream::GroupOpen => (score, nesting_level + 1),
-            Stream::GroupClose => (score + nesting_level, nesting_level - 1),
-            Stream::Garbage(_) => (score, nesting_level),
-        })
-        .0
+fn pt1(input: &Thing) -> u32 {
+    fn score(thing: &Thing, nesting_level: u32) -> u32 {
+        match thing {
+            Thing::Group(things) => {
+                let nested = things.iter().map(|t| score(t, nesting_level + 1));
+                nesting_level + nested.sum::<u32>()
+            }
+            Thing::Garbage(_) => 0,
+        }
+    }
+    score(input, 1)
 }
 
-fn pt2(input: &[Stream]) -> u32 {
-    input
-        .iter()
-        .filter_map(|s| match s {
-            Stream::Garbage(g) => Some(g),
-            _ => None,
-        })
-        .map(|garbage| {
+fn pt2(input: &Thing) -> u32 {
+    match input {
+        Thing::Group(things) => things.iter().map(pt2).sum(),
+        Thing::Garbage(garbage) => {
             garbage
                 .iter()
                 .fold((0, false), |(count, is_escaped), &c| {
@@ -38,11 +35,11 @@ fn pt2(input: &[Stream]) -> u32 {
                     }
                 })
                 .0
-        })
-        .sum()
+        }
+    }
 }
 
-fn parse(input: &[u8]) -> Result<Vec<Stream>> {
+fn parse(input: &[u8]) -> Result<Thing> {
     use parsers::*;
     let garbage = token(b'<')
         .then(
@@ -62,11 +59,15 @@ fn parse(input: &[u8]) -> Result<Vec<Stream>> {
             .map(|n| n.unwrap_or(b"")),
         )
         .trailed(token(b'>'))
-        .map(Stream::Garbage);
-    let open = token((b'{', Stream::GroupOpen));
-    let close = token((b'}', Stream::GroupClose));
-    let section = open.or(close).or(garbage);
-    token(b',').opt().then(section).repeat_into().execute(input)
+        .map(Thing::Garbage);
+    let thing = recursive(|thing| {
+        let group = token(b'{')
+            .then(thing.sep_by(token(b',')).opt())
+            .trailed(token(b'}'))
+            .map(|things| Thing::Group(things.unwrap_or_default()));
+        group.or(garbage)
+    });
+    thing.execute(input)
 }
 
 tests! {
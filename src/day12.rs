@@ -13,22 +13,10 @@ fn pt1(input: &[Connections]) -> usize {
 }
 
 fn pt2(input: &[Connections]) -> usize {
-    let mut visited = vec![false; input.len()];
-    let mut groups = 0;
-    while let Some(start_index) = visited.iter().position(|&visited| !visited) {
-        groups += 1;
-        graph::dfs(start_index, |index| {
-            if visited[index] {
-                None
-            } else {
-                visited[index] = true;
-                Some(input[index].iter().map(|&nr| nr as usize))
-            }
-            .into_iter()
-            .flatten()
-        });
-    }
-    groups
+    graph::connected_components(0..input.len(), |&index| {
+        input[index].iter().map(|&nr| nr as usize)
+    })
+    .count()
 }
 
 fn parse(input: &[u8]) -> Result<Vec<Connections>> {
@@ -46,7 +34,7 @@ fn parse(input: &[u8]) -> Result<Vec<Connections>> {
             Some(v)
         }
     })
-    .map_res(|opt| opt.ok_or(ParseError::Custom("indices are not consecutive")))
+    .map_res(|opt| opt.ok_or(ParseError::custom("indices are not consecutive")))
     .execute(input)
 }
 
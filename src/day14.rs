@@ -23,37 +23,22 @@ fn pt1(input: &AStr) -> u32 {
         .sum()
 }
 
-fn pt2(input: &AStr) -> u32 {
+fn pt2(input: &AStr) -> usize {
     type V = Vec2<u8>;
-    let mut all_cells = HashSet::new();
+    let mut all_cells = Vec::new();
     for (y, row) in iterate_rows(input).enumerate() {
         let y = y as u8;
         for (byte_index, byte) in row.iter().enumerate() {
             let x = byte_index as u8 * 8;
             for bit in 0..8 {
                 if byte & (1 << (7 - bit)) != 0 {
-                    all_cells.insert(V::new(x + bit as u8, y));
+                    all_cells.push(V::new(x + bit as u8, y));
                 }
             }
         }
     }
 
-    let mut regions = 0;
-    while !all_cells.is_empty() {
-        let starting_point = *all_cells.iter().next().unwrap();
-        graph::dfs(starting_point, |point| {
-            if all_cells.remove(&point) {
-                Some(point.neighbors(&Offset::ORTHOGONAL))
-            } else {
-                None
-            }
-            .into_iter()
-            .flatten()
-        });
-        regions += 1;
-    }
-
-    regions
+    graph::connected_components(all_cells, |point| point.neighbors(&Offset::ORTHOGONAL)).count()
 }
 
 fn parse(input: &[u8]) -> Result<&AStr> {
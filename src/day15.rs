@@ -1,4 +1,4 @@
-framework::day!(15, parse => pt1, pt2);
+framework::day!(15, parse => pt1, pt2; slow);
 
 struct Generator {
     factor: u64,
@@ -1,3 +1,5 @@
+use framework::cycle;
+
 framework::day!(16, parse => pt1, pt2);
 
 #[derive(Debug, Clone, Copy)]
@@ -33,23 +35,12 @@ fn pt1(moves: &[Move]) -> AString {
 }
 
 fn pt2(moves: &[Move]) -> AString {
-    let mut order = initial_order::<16>();
-    let mut seen = HashMap::new();
-    seen.insert(order, 0);
-
-    let mut index = 0;
-    loop {
-        index += 1;
+    let dance_once = |order: &[u8; 16]| {
+        let mut order = *order;
         dance(&mut order, moves);
-        if let Some(previous_index) = seen.insert(order, index) {
-            let interval = index - previous_index;
-            let remainder = (1_000_000_000 - index) % interval;
-            for _ in 0..remainder {
-                dance(&mut order, moves);
-            }
-            return order.to_vec();
-        }
-    }
+        order
+    };
+    cycle::nth_state(initial_order::<16>(), dance_once, 1_000_000_000).to_vec()
 }
 
 fn parse(input: &[u8]) -> Result<Vec<Move>> {
@@ -78,13 +78,13 @@ fn pt1(instructions: &[Instruction]) -> Result<Value> {
         match vm.next() {
             ExecRes::Continuing => {}
             ExecRes::Terminated => {
-                break Err(Error::InvalidInput("exited before a value was recalled"))
+                break Err(Error::invalid_input("exited before a value was recalled"))
             }
             ExecRes::Sending(r) => last_value = Some(vm.r(r)),
             ExecRes::Receiving(r) => {
                 if vm.r(r) != 0 {
                     break last_value
-                        .ok_or(Error::InvalidInput("Recover before any sound was played"));
+                        .ok_or(Error::invalid_input("Recover before any sound was played"));
                 }
             }
         }
@@ -165,6 +165,21 @@ pub enum Instruction {
     Sub(Register, Source),
 }
 
+const MNEMONICS: [&[u8; 3]; 9] = [
+    b"snd", b"set", b"add", b"mul", b"mod", b"rcv", b"jgz", b"jnz", b"sub",
+];
+
+/// Explains why an instruction didn't match any of the known forms.
+fn invalid_instruction(mnemonic: &[u8; 3]) -> parsers::ParseError {
+    use parsers::*;
+    if MNEMONICS.contains(&mnemonic) {
+        let mnemonic = mnemonic.escape_ascii();
+        ParseError::custom(format!("invalid operands for `{mnemonic}`"))
+    } else {
+        ParseError::expected(|| MNEMONICS.map(|m| Expectation::Token(m.to_vec())))
+    }
+}
+
 pub fn parse(input: &[u8]) -> Result<Vec<Instruction>> {
     use parsers::*;
     let register = pattern!(b'a'..=b'z').map(|l| Source::Register(l - b'a'));
@@ -186,7 +201,7 @@ pub fn parse(input: &[u8]) -> Result<Vec<Instruction>> {
             // Only used for day 23:
             (b"jnz",                  a,  Some(b)) => Instruction::Jnz(a, b),
             (b"sub", Source::Register(a), Some(b)) => Instruction::Sub(a, b),
-            _ => return Err(ParseError::TokenDoesNotMatch),
+            _                                    => return Err(invalid_instruction(&mnemonic)),
         }));
 
     instruction.sep_by(token(b'\n')).execute(input)
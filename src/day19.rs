@@ -1,3 +1,5 @@
+use framework::grid::Grid;
+
 framework::day!(19, parse => pt1, pt2);
 
 type Vec2 = framework::vecs::Vec2<usize>;
@@ -23,8 +25,12 @@ fn pts(input: &Input) -> Result<(usize, String)> {
                 match (can_rot_90, can_rot_270) {
                     (true, false) => dir = dir.rot_90(),
                     (false, true) => dir = dir.rot_270(),
-                    (true, true) => return Err(Error::InvalidInput("ambiguous corner")),
-                    (false, false) => return Err(Error::InvalidInput("dead end")),
+                    (true, true) => {
+                        return Err(Error::invalid_input(format!("ambiguous corner at {pos}")))
+                    }
+                    (false, false) => {
+                        return Err(Error::invalid_input(format!("dead end at {pos}")))
+                    }
                 }
             }
             b'-' | b'|' => {}
@@ -32,7 +38,7 @@ fn pts(input: &Input) -> Result<(usize, String)> {
                 visited.push(c as char);
             }
             b' ' => break,
-            _ => return Err(Error::InvalidInput("unexpected char")),
+            _ => return Err(Error::invalid_input("unexpected char")),
         }
     }
 
@@ -47,60 +53,27 @@ fn pt2(input: &Input) -> Result<usize> {
     pts(input).map(|(s, _)| s)
 }
 
-struct Input<'i> {
-    data: &'i [u8],
-    width: usize,
+struct Input {
+    grid: Grid<u8>,
     starting_point: usize,
-    height: usize,
-}
-
-trait Get<T> {
-    fn get(&self, index: T) -> u8;
-}
-
-impl Get<Vec2> for Input<'_> {
-    fn get(&self, index: Vec2) -> u8 {
-        if index.x < self.width && index.y < self.height {
-            self.data[index.x + index.y * (self.width + 1)]
-        } else {
-            b' '
-        }
-    }
 }
 
-impl Get<Option<Vec2>> for Input<'_> {
-    fn get(&self, index: Option<Vec2>) -> u8 {
-        index.map(|index| self.get(index)).unwrap_or(b' ')
+impl Input {
+    fn get(&self, pos: impl Into<Option<Vec2>>) -> u8 {
+        self.grid.get_or(pos, b' ')
     }
 }
 
 fn parse(input: &[u8]) -> Result<Input> {
-    let line_width = input
-        .iter()
-        .position(|c| *c == b'\n')
-        .ok_or(Error::InvalidInput("no newline"))?
-        + 1;
-    let starting_point = input[..line_width]
-        .iter()
-        .position(|c| *c == b'|')
-        .ok_or(Error::InvalidInput("no starting point"))?;
-    if input.len() % line_width != 0 {
-        return Err(Error::InvalidInput("expected rectangular grid"));
-    }
-    let height = input.len() / line_width;
-    for y in 0..height {
-        if input[y * line_width + line_width - 1] != b'\n' {
-            return Err(Error::InvalidInput(
-                "expected line endings at regular interval",
-            ));
-        }
-    }
+    use parsers::*;
+    let grid = grid(pattern!(b' ' | b'|' | b'-' | b'+' | b'A'..=b'Z')).execute(input)?;
+    let starting_point = (0..grid.width())
+        .find(|&x| grid[Vec2::new(x, 0)] == b'|')
+        .ok_or(Error::invalid_input("no starting point"))?;
 
     Ok(Input {
-        data: input,
-        width: line_width - 1,
+        grid,
         starting_point,
-        height,
     })
 }
 
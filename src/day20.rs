@@ -1,3 +1,4 @@
+use framework::parsers::Parse;
 use num::Zero;
 use std::{cmp::Ordering, num::NonZeroI32};
 
@@ -22,7 +23,7 @@ fn pt1(particles: &[Particle]) -> Result<usize> {
         })
         .map(|(_, index)| index)
         .ok()
-        .ok_or(Error::InvalidInput(
+        .ok_or(Error::invalid_input(
             "multiple particles have the same absolute acceleration",
         ))
 }
@@ -224,33 +225,44 @@ impl Roots {
 
 type Vec3 = framework::vecs::Vec3<i32>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Parse)]
+#[parse(sep = ", ", context = "particle")]
 struct Particle {
+    #[parse(prefix = "p = ", with = "vec3()", context = "position")]
     pos: Vec3,
+    #[parse(prefix = "v = ", with = "vec3()", context = "velocity")]
     vel: Vec3,
+    #[parse(prefix = "a = ", with = "vec3()", context = "acceleration")]
     acc: Vec3,
 }
 
+fn vec3<'s>() -> impl parsers::Parser<'s, Output = Vec3> {
+    use parsers::*;
+    let nr = number::<i32>().lexeme();
+    let cnr = token(b',').lexeme().then(nr);
+    let vec3 = token(b'<')
+        .lexeme()
+        .then(nr)
+        .and(cnr)
+        .and(cnr)
+        .trailed(token(b'>'));
+    vec3.map(|((x, y), z)| Vec3::new(x, y, z))
+}
+
 fn parse(input: &[u8]) -> Result<Vec<Particle>> {
     use parsers::*;
-    let nr = number::<i32>();
-    let cnr = token(b',').then(nr);
-    let vec3 = token(b'<').then(nr).and(cnr).and(cnr).trailed(token(b'>'));
-    let vec3 = vec3.map(|((x, y), z)| Vec3::new(x, y, z));
-    let pos = token(b"p=").then(vec3);
-    let vel = token(b"v=").then(vec3);
-    let acc = token(b"a=").then(vec3);
-    let particle = pos
-        .and(token(b", ").then(vel))
-        .and(token(b", ").then(acc))
-        .map(|((pos, vel), acc)| Particle { pos, vel, acc });
-    particle.sep_by(token(b'\n')).execute(input)
+    ws().then(Particle::parser().sep_by(phrase(b"\n")))
+        .trailed(ws())
+        .execute(input)
 }
 
 tests! {
     test_pt!(parse, pt1, b"\
 p=<3,0,0>, v=<2,0,0>, a=<-1,0,0>
 p=<4,0,0>, v=<0,0,0>, a=<-2,0,0>
+" => 0, b"\
+p=< 3, 0, 0>, v=< 2, 0, 0>, a=<-1, 0, 0>
+p=< 4, 0, 0>, v=< 0, 0, 0>, a=<-2, 0, 0>  
 " => 0);
     test_pt!(parse, pt2, b"\
 p=<-6,0,0>, v=<3,0,0>, a=<0,0,0>
@@ -10,6 +10,20 @@ type Vec2 = framework::vecs::Vec2<usize>;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Grid<const N: usize>(u16);
 
+impl<const N: usize> Grid<N> {
+    /// Packs a pattern of N by N cells, with the cell at (x, y) in bit
+    /// x + y * N.
+    fn from_cells(cells: &framework::grid::Grid<bool>) -> Self {
+        debug_assert_eq!(Vec2::new(N, N), cells.size());
+        Self(
+            cells
+                .iter()
+                .map(|(pos, &bit)| (bit as u16) << (pos.x + pos.y * N))
+                .sum(),
+        )
+    }
+}
+
 trait Transformable: Copy + Ord {
     fn flip_v(self) -> Self;
     fn rot_90(self) -> Self;
@@ -220,39 +234,46 @@ fn pt2(rules: &Rules) -> usize {
     count_after_iterations::<18>(rules)
 }
 
+enum Rule {
+    TwoToThree(Grid<2>, Grid<3>),
+    ThreeToFour(Grid<3>, Grid<4>),
+}
+
 fn parse(input: &[u8]) -> Result<Rules> {
     use parsers::*;
 
     let bit = token((b'.', false)).or(token((b'#', true)));
-    let sep = token(b'/');
-    let arrow = token(b" => ");
-
-    let b2 = bit.many_n::<2>().map(|[a, b]| a as u16 | (b as u16) << 1);
-    let b3 = bit
-        .many_n::<3>()
-        .map(|[a, b, c]| a as u16 | (b as u16) << 1 | (c as u16) << 2);
-    let b4 = bit
-        .many_n::<4>()
-        .map(|[a, b, c, d]| a as u16 | (b as u16) << 1 | (c as u16) << 2 | (d as u16) << 3);
-    let b2x2 = b2.and(sep.then(b2)).map(|(r1, r2)| Grid::<2>(r1 | r2 << 2));
-    let b3x3 = b3
-        .and(sep.then(b3))
-        .and(sep.then(b3))
-        .map(|((r1, r2), r3)| Grid::<3>(r1 | r2 << 3 | r3 << 6));
-    let b4x4 = b4
-        .and(sep.then(b4))
-        .and(sep.then(b4))
-        .and(sep.then(b4))
-        .map(|(((r1, r2), r3), r4)| Grid::<4>(r1 | r2 << 4 | r3 << 8 | r4 << 12));
-
-    let two_to_three = b2x2.and(arrow.then(b3x3)).sep_by(token(b'\n'));
-    let three_to_four = b3x3.and(arrow.then(b4x4)).sep_by(token(b'\n'));
-
-    two_to_three
-        .and(token(b'\n').then(three_to_four))
-        .map(|(two_to_three, three_to_four)| Rules {
-            two_to_three,
-            three_to_four,
+    let pattern = grid_sep_by(bit, token(b'/'));
+    let rule = pattern
+        .and(token(b" => ").then(pattern))
+        .map_res(|(from, to)| match (from.size(), to.size()) {
+            (Vec2 { x: 2, y: 2 }, Vec2 { x: 3, y: 3 }) => Ok(Rule::TwoToThree(
+                Grid::from_cells(&from),
+                Grid::from_cells(&to),
+            )),
+            (Vec2 { x: 3, y: 3 }, Vec2 { x: 4, y: 4 }) => Ok(Rule::ThreeToFour(
+                Grid::from_cells(&from),
+                Grid::from_cells(&to),
+            )),
+            _ => Err(ParseError::custom(
+                "expected a rule from 2x2 to 3x3, or from 3x3 to 4x4",
+            )),
+        });
+
+    rule.sep_by::<_, Vec<_>>(token(b'\n'))
+        .map(|rules| {
+            let mut two_to_three = Vec::new();
+            let mut three_to_four = Vec::new();
+            for rule in rules {
+                match rule {
+                    Rule::TwoToThree(from, to) => two_to_three.push((from, to)),
+                    Rule::ThreeToFour(from, to) => three_to_four.push((from, to)),
+                }
+            }
+            Rules {
+                two_to_three,
+                three_to_four,
+            }
         })
         .execute(input)
 }
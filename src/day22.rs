@@ -1,4 +1,4 @@
-framework::day!(22, parse => pt1, pt2);
+framework::day!(22, parse => pt1, pt2; slow);
 
 type Vec2 = framework::vecs::Vec2<i32>;
 
@@ -61,48 +61,22 @@ fn pt2(state: &State) -> usize {
 }
 
 fn parse(input: &[u8]) -> Result<State> {
-    let mut board = HashMap::new();
-    let mut width = None;
-    let mut x = 0;
-    let mut y = 0;
-    for &c in input.iter() {
-        let cell_value = match c {
-            b'\n' => {
-                if x == 0 {
-                    return Err(Error::InvalidInput("empty line in input"));
-                }
-                if width.is_some() && width != Some(x) {
-                    return Err(Error::InvalidInput(
-                        "different lines have different lengths",
-                    ));
-                }
-                width = Some(x);
-                x = 0;
-                y += 1;
-                continue;
-            }
-            b'.' => Cell::Clean,
-            b'#' => Cell::Infected,
-            _ => return Err(Error::InvalidInput("unexpected character in input")),
-        };
-        board.insert(Vec2::new(x, y), cell_value);
-        x += 1;
-    }
-
-    let width = width.ok_or(Error::InvalidInput("empty input"))?;
-    if x != 0 {
-        return Err(Error::InvalidInput("expected newline at the end"));
-    }
-    let height = y;
-    if width % 2 == 0 || height % 2 == 0 {
-        return Err(Error::InvalidInput(
+    use parsers::*;
+    let cell = token((b'.', Cell::Clean)).or(token((b'#', Cell::Infected)));
+    let grid = grid(cell).execute(input)?;
+    if grid.width() % 2 == 0 || grid.height() % 2 == 0 {
+        return Err(Error::invalid_input(
             "input grid has an even dimension, which does not unambiguously specify a middle",
         ));
     }
 
+    let to_i32 = |pos: framework::vecs::Vec2<usize>| Vec2::new(pos.x as i32, pos.y as i32);
     Ok(State {
-        board,
-        position: Vec2::new(width / 2, height / 2),
+        board: grid
+            .iter()
+            .map(|(pos, &cell)| (to_i32(pos), cell))
+            .collect(),
+        position: Vec2::new(grid.width() as i32 / 2, grid.height() as i32 / 2),
         direction: Offset::Y_NEG,
     })
 }
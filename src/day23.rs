@@ -15,7 +15,7 @@ fn pt1(instructions: &[Instruction]) -> Result<u32> {
             ExecRes::Continuing => continue,
             ExecRes::Terminated => break,
             _ => {
-                return Err(Error::InvalidInput(
+                return Err(Error::invalid_input(
                     "transmission instructions not supported",
                 ))
             }
@@ -34,11 +34,13 @@ fn pt1(instructions: &[Instruction]) -> Result<u32> {
 // which are not prime.
 fn pt2(instructions: &[Instruction]) -> Result<usize> {
     let input: u64 = if let Instruction::Set(1, Source::Value(input)) = instructions[0] {
-        input.try_into().ok().ok_or(Error::InvalidInput(
+        input.try_into().ok().ok_or(Error::invalid_input(
             "initial value for b cannot be negative",
         ))?
     } else {
-        return Err(Error::InvalidInput("expected input to start with: set b X"));
+        return Err(Error::invalid_input(
+            "expected input to start with: set b X",
+        ));
     };
 
     let lower = input * 100 + 100_000;
@@ -6,7 +6,7 @@ type Port = (u32, u32);
 
 fn iterate_connections(ports: &[Port], mut f: impl FnMut(&[Port])) -> Result<()> {
     if ports.len() >= 64 {
-        return Err(Error::InvalidInput("too many ports in input"));
+        return Err(Error::invalid_input("too many ports in input"));
     }
     let mut lookups = Vec::with_capacity(ports.len() * 2);
     lookups.extend(ports.iter().enumerate().map(|(i, &p)| (p, i)));
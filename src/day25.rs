@@ -1,6 +1,7 @@
+use framework::parsers::Parse;
 use std::collections::hash_map::Entry;
 
-framework::day!(25, parse => pt1, pt2);
+framework::day!(25, parse => pt1, pt2; slow);
 
 struct TuringMachineBlueprint {
     initial_state: u8,
@@ -52,9 +53,11 @@ impl<'bp> TuringMachine<'bp> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Parse)]
 enum Direction {
+    #[parse(token = "left")]
     Left,
+    #[parse(token = "right")]
     Right,
 }
 
@@ -63,9 +66,20 @@ struct Instruction {
     when_true: Actions,
 }
 
+#[derive(Parse)]
 struct Actions {
+    #[parse(
+        prefix = ":\n    - Write the value ",
+        with = "parsers::token((b'0', false)).or(parsers::token((b'1', true)))"
+    )]
     write_value: bool,
+    #[parse(prefix = ".\n    - Move one slot to the ")]
     move_direction: Direction,
+    #[parse(
+        prefix = ".\n    - Continue with state ",
+        suffix = ".",
+        with = "state()"
+    )]
     next_state: u8,
 }
 
@@ -81,34 +95,25 @@ fn pt2(_: &TuringMachineBlueprint) -> &'static AStr {
     b"gg"
 }
 
+fn state<'s>() -> impl parsers::Parser<'s, Output = u8> {
+    parsers::pattern!(b'A'..=b'Z')
+}
+
 fn parse(input: &[u8]) -> Result<TuringMachineBlueprint> {
     use parsers::*;
-    let state = pattern!(b'A'..=b'Z');
-    let preamble = token(b"Begin in state ")
-        .then(state)
-        .trailed(token(b".\nPerform a diagnostic checksum after "))
+    let preamble = phrase(b"Begin in state ")
+        .then(state())
+        .trailed(phrase(b".\nPerform a diagnostic checksum after "))
         .and(number::<u32>())
-        .trailed(token(b" steps.\n\n"));
-
-    let action = token(b":\n    - Write the value ")
-        .then(token((b'0', false)).or(token((b'1', true))))
-        .trailed(token(b".\n    - Move one slot to the "))
-        .and(token((b"left", Direction::Left)).or(token((b"right", Direction::Right))))
-        .trailed(token(b".\n    - Continue with state "))
-        .and(state)
-        .trailed(token(b"."))
-        .map(|((write_value, move_direction), next_state)| Actions {
-            write_value,
-            move_direction,
-            next_state,
-        });
-
-    let instruction = token(b"In state ")
-        .then(state)
-        .trailed(token(b":\n  If the current value is 0"))
-        .and(action)
-        .trailed(token(b"\n  If the current value is 1"))
-        .and(action)
+        .trailed(phrase(b" steps.\n\n"))
+        .context("preamble");
+
+    let instruction = phrase(b"In state ")
+        .then(state())
+        .trailed(phrase(b":\n  If the current value is 0"))
+        .and(Actions::parser().context("actions for 0"))
+        .trailed(phrase(b"\n  If the current value is 1"))
+        .and(Actions::parser().context("actions for 1"))
         .map(|((state, when_false), when_true)| {
             (
                 state,
@@ -117,11 +122,13 @@ fn parse(input: &[u8]) -> Result<TuringMachineBlueprint> {
                     when_true,
                 },
             )
-        });
+        })
+        .context("state");
 
-    let instructions = instruction.sep_by(token(b"\n\n"));
-    preamble
+    let instructions = instruction.sep_by(phrase(b"\n\n"));
+    ws().then(preamble)
         .and(instructions)
+        .trailed(ws())
         .map(
             |((initial_state, checksum_after), instructions)| TuringMachineBlueprint {
                 initial_state,
@@ -156,6 +163,30 @@ In state B:
     - Write the value 1.
     - Move one slot to the right.
     - Continue with state A.";
+    const REINDENTED: &[u8] = b"\
+Begin in state A.
+Perform a diagnostic checksum after 6 steps.
 
-    test_pt!(parse, pt1, EXAMPLE => 3);
+In state A:
+\tIf the current value is 0:
+\t- Write the value 1.
+\t- Move one slot to the right.
+\t- Continue with state B.
+\tIf the current value is 1:
+\t- Write the value 0.
+\t- Move one slot to the left.
+\t- Continue with state B.
+\x20
+In state B:
+ If the current value is 0:
+  - Write the value 1.  
+  - Move one slot to the left.
+  - Continue with state A.
+ If the current value is 1:
+  - Write the value 1.
+  - Move one slot to the right.
+  - Continue with state A.
+";
+
+    test_pt!(parse, pt1, EXAMPLE => 3, REINDENTED => 3);
 }
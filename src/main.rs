@@ -12,6 +12,7 @@
 mod prelude;
 
 framework::main!(
+    2017;
     day01,
     day02,
     day03,
@@ -1,5 +1,4 @@
 pub use ahash::AHashMap as HashMap;
-pub use ahash::AHashSet as HashSet;
 pub use arrayvec::{ArrayString, ArrayVec};
 pub use framework::prelude::*;
 pub use itertools::Itertools;